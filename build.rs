@@ -41,10 +41,10 @@ fn extract_datalang_blocks(content: &str) -> Vec<(usize, String)> {
 fn extract_from_datalang_macro(code: &str) -> String {
     let re = Regex::new(r"(?s)datalang!\s*\{\s*(.*?)\s*\}").unwrap();
 
-    if let Some(captures) = re.captures(code) {
-        if let Some(inner_match) = captures.get(1) {
-            return inner_match.as_str().to_string();
-        }
+    if let Some(captures) = re.captures(code)
+        && let Some(inner_match) = captures.get(1)
+    {
+        return inner_match.as_str().to_string();
     }
 
     code.to_string()
@@ -87,14 +87,10 @@ fn validate_markdown_file(file_path: &Path) -> Result<Vec<String>, String> {
 
 fn main() {
     let text_definitions_dir = "dirctionary_tests/text_definitions";
-    let macro_definitions_dir = "dirctionary_tests/macro_definitions";
-
-    // Create the macro_definitions directory if it doesn't exist
-    fs::create_dir_all(macro_definitions_dir).unwrap();
 
     let mut validated_files = Vec::new();
 
-    // Read all .txt files from text_definitions
+    // Validate all .txt files from text_definitions, they are expanded by datalang_file!
     let text_dir = Path::new(text_definitions_dir);
     if text_dir.exists() && text_dir.is_dir() {
         for entry in fs::read_dir(text_dir).unwrap() {
@@ -105,12 +101,14 @@ fn main() {
                 let file_stem = path.file_stem().unwrap().to_str().unwrap();
                 let content = fs::read_to_string(&path).unwrap();
 
-                // Validate the content before generating
                 match shared::DataLangFile::parse_from_str(&content) {
                     Ok(parsed) => {
                         if let Err(validation_error) = parsed.validate() {
                             panic!("Validation failed for {file_stem}.txt: {validation_error}");
                         }
+                        for warning in parsed.deprecation_warnings() {
+                            println!("cargo:warning=DataLang: {file_stem}.txt: {warning}");
+                        }
                         validated_files.push(file_stem.to_string());
                     }
                     Err(parse_error) => {
@@ -118,50 +116,7 @@ fn main() {
                     }
                 }
 
-                // Generate the .rs file with datalang! macro wrapper
-                // Add leading tab to each line of content
-                let indented_content = content
-                    .lines()
-                    .map(|line| format!("\t{line}"))
-                    .collect::<Vec<_>>()
-                    .join("\n");
-
-                let rust_content = [
-                    &format!("// This file is automatically generated from {file_stem}.txt"),
-                    "// DO NOT EDIT MANUALLY - your changes will be overwritten",
-                    "//",
-                    "// To regenerate this file:",
-                    &format!("// 1. Edit {file_stem}.txt in the text_definitions/ directory"),
-                    "// 2. Run `cargo build` or `cargo check` to trigger regeneration",
-                    "",
-                    "use datalang::datalang;",
-                    "",
-                    "datalang! {",
-                    &indented_content,
-                    "}",
-                    "",
-                ]
-                .join("\n");
-
-                let output_path = Path::new(macro_definitions_dir).join(format!("{file_stem}.rs"));
-
-                // Only write if the content is different or file doesn't exist
-                let should_write = if output_path.exists() {
-                    match fs::read_to_string(&output_path) {
-                        Ok(existing_content) => existing_content != rust_content,
-                        Err(_) => true,
-                    }
-                } else {
-                    true
-                };
-
-                if should_write {
-                    fs::write(&output_path, rust_content).unwrap();
-                }
-
                 println!("cargo:rerun-if-changed={}", path.display());
-                // Also track the generated file
-                println!("cargo:rerun-if-changed={}", output_path.display());
             }
         }
     }
@@ -0,0 +1,128 @@
+// Apache Avro export
+// Composite terms and structs become records in the namespace of their dictionary and state
+// machine terms become enums. Simple terms have no Avro equivalent, so fields are strings like
+// the `String` they generate in Rust. Fields with a `@default` keep that value as their Avro
+// default, so readers fill it in for data without them. Other fields data may lack, those with
+// `@since` or `@removed`, are a `["null", "string"]` union defaulting to null. Key order follows
+// the definition file, so unchanged input gives identical output.
+
+use serde_json::{Map, Value, json};
+
+use crate::types::{DataLangFile, DataLangItem, ParseError, ResolvedField, snake_case};
+
+/// Namespace of a dictionary's named types, `SocialMedia` becomes `social_media`
+pub fn namespace(dictionary: &str) -> String {
+    snake_case(dictionary)
+}
+
+/// Avro schema of a field, a union with null when data may lack it and nothing fills it in
+pub fn field_type(field: &ResolvedField) -> Value {
+    if is_nullable(field) {
+        json!(["null", "string"])
+    } else {
+        json!("string")
+    }
+}
+
+fn is_nullable(field: &ResolvedField) -> bool {
+    field.is_optional() && field.default.is_none()
+}
+
+/// Schemas of every record and enum in a validated definition file, in definition order
+pub fn generate(file: &DataLangFile) -> Result<Vec<Value>, ParseError> {
+    let mut schemas = Vec::new();
+    for item in &file.items {
+        match item {
+            DataLangItem::Term { name, .. } | DataLangItem::Struct { name, .. } => {
+                if !file.is_simple_term(name) {
+                    schemas.push(record(file, name, &file.expand_fields(name)?));
+                }
+            }
+            DataLangItem::States { name, states, .. } => {
+                schemas.push(enumeration(file, name, states));
+            }
+            DataLangItem::Dictionary { .. } | DataLangItem::Import { .. } => {}
+        }
+    }
+    Ok(schemas)
+}
+
+/// Schema of one record or enum
+pub fn schema(file: &DataLangFile, name: &str) -> Result<Value, ParseError> {
+    if let Some((states, _)) = file.state_machine(name) {
+        return Ok(enumeration(file, name, states));
+    }
+    if file.get_fields(name).is_none() || file.is_simple_term(name) {
+        return Err(ParseError::StructuralError {
+            context: name.to_string(),
+            issue: "no composite term, struct or state machine with this name is defined"
+                .to_string(),
+        });
+    }
+    Ok(record(file, name, &file.expand_fields(name)?))
+}
+
+/// Every schema as one `.avsc` document: a single schema, or a union of all of them
+pub fn to_string(file: &DataLangFile) -> Result<String, ParseError> {
+    let mut schemas = generate(file)?;
+    let document = if schemas.len() == 1 {
+        schemas.remove(0)
+    } else {
+        Value::Array(schemas)
+    };
+    Ok(serde_json::to_string_pretty(&document).expect("schemas only hold strings and maps"))
+}
+
+fn record(file: &DataLangFile, name: &str, fields: &[ResolvedField]) -> Value {
+    let fields: Vec<Value> = fields
+        .iter()
+        .map(|field| {
+            let mut schema = Map::new();
+            schema.insert("name".to_string(), json!(field.field_name()));
+            schema.insert("type".to_string(), field_type(field));
+            // Simple terms have no schema of their own to carry their doc
+            if let Some(doc) = &field.doc {
+                schema.insert("doc".to_string(), json!(doc));
+            }
+            // The default of a union must match its first branch
+            if let Some(default) = &field.default {
+                schema.insert("default".to_string(), json!(default));
+            } else if is_nullable(field) {
+                schema.insert("default".to_string(), Value::Null);
+            }
+            if let Some(deprecated) = &field.deprecated {
+                schema.insert("deprecated".to_string(), json!(deprecated.note));
+            }
+            Value::Object(schema)
+        })
+        .collect();
+
+    let mut schema = named(file, "record", name);
+    schema.insert("fields".to_string(), Value::Array(fields));
+    Value::Object(schema)
+}
+
+/// The initial state is the default, so readers can resolve states they do not know
+fn enumeration(file: &DataLangFile, name: &str, states: &[String]) -> Value {
+    let mut schema = named(file, "enum", name);
+    schema.insert("symbols".to_string(), json!(states));
+    schema.insert("default".to_string(), json!(states[0]));
+    Value::Object(schema)
+}
+
+/// `type`, `name`, `namespace`, `doc` and `deprecated`, shared by records and enums
+fn named(file: &DataLangFile, kind: &str, name: &str) -> Map<String, Value> {
+    let mut schema = Map::new();
+    schema.insert("type".to_string(), json!(kind));
+    schema.insert("name".to_string(), json!(name));
+    if let Some(dictionary) = file.dictionary_name() {
+        schema.insert("namespace".to_string(), json!(namespace(dictionary)));
+    }
+    if let Some(doc) = file.doc(name) {
+        schema.insert("doc".to_string(), json!(doc));
+    }
+    if let Some(deprecation) = file.deprecation(name) {
+        schema.insert("deprecated".to_string(), json!(deprecation.note));
+    }
+    schema
+}
@@ -0,0 +1,437 @@
+// Compatibility between two versions of a definition file
+// Every term and struct is expanded in both versions and compared field by field, and state
+// machine terms are compared by their states and transitions.
+// "Backward" means consumers built against the new version can read data written with
+// the old one, "forward" means consumers built against the old version can read data
+// written with the new one.
+
+use std::fmt;
+
+use serde::Serialize;
+
+use crate::types::{DataLangFile, DataLangItem, ParseError, ResolvedField, Transition};
+
+/// How a change affects consumers on either side of it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Compatibility {
+    /// Old and new consumers can read data written with either version
+    Full,
+    /// New consumers can read old data
+    Backward,
+    /// Old consumers can read new data
+    Forward,
+    /// Neither direction is safe
+    Breaking,
+}
+
+impl Compatibility {
+    /// Compatibility of two changes applied together
+    pub fn combine(self, other: Compatibility) -> Compatibility {
+        match (self, other) {
+            (Compatibility::Full, other) | (other, Compatibility::Full) => other,
+            (left, right) if left == right => left,
+            _ => Compatibility::Breaking,
+        }
+    }
+}
+
+impl fmt::Display for Compatibility {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Compatibility::Full => "fully compatible",
+            Compatibility::Backward => "backward compatible",
+            Compatibility::Forward => "forward compatible",
+            Compatibility::Breaking => "breaking",
+        })
+    }
+}
+
+/// What changed between the two versions
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeKind {
+    DictionaryChanged,
+    TermAdded,
+    TermRemoved,
+    StructAdded,
+    StructRemoved,
+    FieldAdded,
+    FieldRemoved,
+    OriginChanged,
+    CardinalityTightened,
+    CardinalityLoosened,
+    FieldsReordered,
+    /// A term with fields became a state machine or the other way around
+    TermKindChanged,
+    StateAdded,
+    StateRemoved,
+    TransitionAdded,
+    TransitionRemoved,
+}
+
+impl ChangeKind {
+    /// Compatibility of a single change of this kind. Fields are taken to be required, see
+    /// `field_compatibility` for optional ones.
+    pub fn compatibility(self) -> Compatibility {
+        match self {
+            ChangeKind::TermAdded | ChangeKind::StructAdded | ChangeKind::FieldsReordered => {
+                Compatibility::Full
+            }
+            // Old data lacks the field, new data has an extra one old consumers ignore
+            ChangeKind::FieldAdded | ChangeKind::CardinalityTightened => Compatibility::Forward,
+            // New consumers ignore the old field, old consumers miss it in new data
+            ChangeKind::FieldRemoved | ChangeKind::CardinalityLoosened => Compatibility::Backward,
+            // Old consumers reject the new state or move in new data
+            ChangeKind::StateAdded | ChangeKind::TransitionAdded => Compatibility::Backward,
+            ChangeKind::DictionaryChanged
+            | ChangeKind::TermRemoved
+            | ChangeKind::StructRemoved
+            | ChangeKind::OriginChanged
+            | ChangeKind::TermKindChanged
+            | ChangeKind::StateRemoved
+            | ChangeKind::TransitionRemoved => Compatibility::Breaking,
+        }
+    }
+}
+
+/// Whether a field must be present
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Cardinality {
+    Optional,
+    Required,
+}
+
+fn cardinality(field: &ResolvedField) -> Cardinality {
+    if field.is_optional() {
+        Cardinality::Optional
+    } else {
+        Cardinality::Required
+    }
+}
+
+/// Compatibility of adding or removing a field. Consumers on either side already cope with
+/// an optional field being absent, so only required ones restrict a direction.
+fn field_compatibility(kind: ChangeKind, field: &ResolvedField) -> Compatibility {
+    match cardinality(field) {
+        Cardinality::Optional => Compatibility::Full,
+        Cardinality::Required => kind.compatibility(),
+    }
+}
+
+/// One classified difference between the two versions
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Change {
+    pub kind: ChangeKind,
+    /// Term or struct the change applies to, or the dictionary name
+    pub item: String,
+    /// Term of the affected field, or the affected state or transition, for changes inside
+    /// a term or struct
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub field: Option<String>,
+    pub compatibility: Compatibility,
+    pub message: String,
+}
+
+/// All changes between two versions and their combined compatibility
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CompatReport {
+    pub compatibility: Compatibility,
+    pub changes: Vec<Change>,
+}
+
+impl CompatReport {
+    pub fn is_breaking(&self) -> bool {
+        self.compatibility == Compatibility::Breaking
+    }
+
+    /// Machine-readable form of the report
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("report serializes to JSON")
+    }
+
+    fn push(&mut self, kind: ChangeKind, item: &str, field: Option<&str>, message: String) {
+        self.push_with(kind, kind.compatibility(), item, field, message);
+    }
+
+    fn push_with(
+        &mut self,
+        kind: ChangeKind,
+        compatibility: Compatibility,
+        item: &str,
+        field: Option<&str>,
+        message: String,
+    ) {
+        self.compatibility = self.compatibility.combine(compatibility);
+        self.changes.push(Change {
+            kind,
+            item: item.to_string(),
+            field: field.map(str::to_string),
+            compatibility,
+            message,
+        });
+    }
+}
+
+impl fmt::Display for CompatReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for change in &self.changes {
+            writeln!(f, "[{}] {}", change.compatibility, change.message)?;
+        }
+        write!(f, "Overall: {}", self.compatibility)
+    }
+}
+
+/// Compare two versions of a definition file
+pub fn check(old: &DataLangFile, new: &DataLangFile) -> Result<CompatReport, ParseError> {
+    let mut report = CompatReport {
+        compatibility: Compatibility::Full,
+        changes: Vec::new(),
+    };
+
+    if old.dictionary_name() != new.dictionary_name() {
+        let name = new
+            .dictionary_name()
+            .or(old.dictionary_name())
+            .unwrap_or_default();
+        report.push(
+            ChangeKind::DictionaryChanged,
+            name,
+            None,
+            format!(
+                "Dictionary changed from {} to {}",
+                old.dictionary_name().unwrap_or("(none)"),
+                new.dictionary_name().unwrap_or("(none)")
+            ),
+        );
+    }
+
+    for old_item in items(old) {
+        match items(new).find(|new_item| new_item.name == old_item.name) {
+            Some(new_item) if new_item.is_term == old_item.is_term => {
+                match (
+                    old.state_machine(old_item.name),
+                    new.state_machine(old_item.name),
+                ) {
+                    (None, None) => compare_fields(&mut report, old_item.name, old, new)?,
+                    (Some(old_machine), Some(new_machine)) => {
+                        compare_states(&mut report, old_item.name, old_machine, new_machine)
+                    }
+                    (old_machine, _) => report.push(
+                        ChangeKind::TermKindChanged,
+                        old_item.name,
+                        None,
+                        if old_machine.is_some() {
+                            format!("Term {} is no longer a state machine", old_item.name)
+                        } else {
+                            format!("Term {} became a state machine", old_item.name)
+                        },
+                    ),
+                }
+            }
+            _ => report.push(
+                old_item.removed_kind(),
+                old_item.name,
+                None,
+                format!("{} {} was removed", old_item.label(), old_item.name),
+            ),
+        }
+    }
+
+    for new_item in items(new) {
+        let existed = items(old)
+            .any(|old_item| old_item.name == new_item.name && old_item.is_term == new_item.is_term);
+        if !existed {
+            report.push(
+                new_item.added_kind(),
+                new_item.name,
+                None,
+                format!("{} {} was added", new_item.label(), new_item.name),
+            );
+        }
+    }
+
+    Ok(report)
+}
+
+struct Item<'a> {
+    name: &'a str,
+    is_term: bool,
+}
+
+impl Item<'_> {
+    fn label(&self) -> &'static str {
+        if self.is_term { "Term" } else { "Struct" }
+    }
+
+    fn added_kind(&self) -> ChangeKind {
+        if self.is_term {
+            ChangeKind::TermAdded
+        } else {
+            ChangeKind::StructAdded
+        }
+    }
+
+    fn removed_kind(&self) -> ChangeKind {
+        if self.is_term {
+            ChangeKind::TermRemoved
+        } else {
+            ChangeKind::StructRemoved
+        }
+    }
+}
+
+fn items(file: &DataLangFile) -> impl Iterator<Item = Item<'_>> {
+    file.items.iter().filter_map(|item| match item {
+        DataLangItem::Term { name, .. } | DataLangItem::States { name, .. } => Some(Item {
+            name,
+            is_term: true,
+        }),
+        DataLangItem::Struct { name, .. } => Some(Item {
+            name,
+            is_term: false,
+        }),
+        _ => None,
+    })
+}
+
+fn compare_fields(
+    report: &mut CompatReport,
+    name: &str,
+    old: &DataLangFile,
+    new: &DataLangFile,
+) -> Result<(), ParseError> {
+    let old_fields = old.expand_fields(name)?;
+    let new_fields = new.expand_fields(name)?;
+
+    for old_field in &old_fields {
+        let term = old_field.term.as_str();
+        let Some(new_field) = new_fields.iter().find(|field| field.term == term) else {
+            report.push_with(
+                ChangeKind::FieldRemoved,
+                field_compatibility(ChangeKind::FieldRemoved, old_field),
+                name,
+                Some(term),
+                format!("Field {term} was removed from {name}"),
+            );
+            continue;
+        };
+
+        if old_field.dictionary != new_field.dictionary {
+            report.push(
+                ChangeKind::OriginChanged,
+                name,
+                Some(term),
+                format!(
+                    "Field {term} of {name} now comes from {} instead of {}",
+                    new_field.dictionary.as_deref().unwrap_or("(none)"),
+                    old_field.dictionary.as_deref().unwrap_or("(none)")
+                ),
+            );
+        }
+        match cardinality(old_field).cmp(&cardinality(new_field)) {
+            std::cmp::Ordering::Less => report.push(
+                ChangeKind::CardinalityTightened,
+                name,
+                Some(term),
+                format!("Field {term} of {name} became required"),
+            ),
+            std::cmp::Ordering::Greater => report.push(
+                ChangeKind::CardinalityLoosened,
+                name,
+                Some(term),
+                format!("Field {term} of {name} became optional"),
+            ),
+            std::cmp::Ordering::Equal => {}
+        }
+    }
+
+    for new_field in &new_fields {
+        let term = new_field.term.as_str();
+        if !old_fields.iter().any(|field| field.term == term) {
+            report.push_with(
+                ChangeKind::FieldAdded,
+                field_compatibility(ChangeKind::FieldAdded, new_field),
+                name,
+                Some(term),
+                format!("Field {term} was added to {name}"),
+            );
+        }
+    }
+
+    // Fields present in both versions but in a different order
+    let kept_old: Vec<&str> = old_fields
+        .iter()
+        .map(|field| field.term.as_str())
+        .filter(|term| new_fields.iter().any(|field| field.term == *term))
+        .collect();
+    let kept_new: Vec<&str> = new_fields
+        .iter()
+        .map(|field| field.term.as_str())
+        .filter(|term| old_fields.iter().any(|field| field.term == *term))
+        .collect();
+    if kept_old != kept_new {
+        report.push(
+            ChangeKind::FieldsReordered,
+            name,
+            None,
+            format!("Fields of {name} were reordered"),
+        );
+    }
+
+    Ok(())
+}
+
+/// States and transitions of a state machine term, as returned by `state_machine`
+type Machine<'a> = (&'a [String], &'a [Transition]);
+
+fn compare_states(report: &mut CompatReport, name: &str, old: Machine, new: Machine) {
+    let (old_states, old_transitions) = old;
+    let (new_states, new_transitions) = new;
+
+    for state in old_states
+        .iter()
+        .filter(|state| !new_states.contains(state))
+    {
+        report.push(
+            ChangeKind::StateRemoved,
+            name,
+            Some(state),
+            format!("State {state} was removed from {name}"),
+        );
+    }
+    for state in new_states
+        .iter()
+        .filter(|state| !old_states.contains(state))
+    {
+        report.push(
+            ChangeKind::StateAdded,
+            name,
+            Some(state),
+            format!("State {state} was added to {name}"),
+        );
+    }
+
+    for (from, to) in old_transitions
+        .iter()
+        .filter(|transition| !new_transitions.contains(transition))
+    {
+        report.push(
+            ChangeKind::TransitionRemoved,
+            name,
+            Some(&format!("{from} -> {to}")),
+            format!("Transition {from} -> {to} was removed from {name}"),
+        );
+    }
+    for (from, to) in new_transitions
+        .iter()
+        .filter(|transition| !old_transitions.contains(transition))
+    {
+        report.push(
+            ChangeKind::TransitionAdded,
+            name,
+            Some(&format!("{from} -> {to}")),
+            format!("Transition {from} -> {to} was added to {name}"),
+        );
+    }
+}
@@ -0,0 +1,341 @@
+// Diagrams of term composition
+// Draws a set of definition files as a Graphviz DOT or Mermaid flowchart. Dictionaries are
+// clusters holding a node per term, state machine and struct. Composite terms have `has` edges
+// to the terms they reference, structs have `include` edges, `-` references are drawn as
+// `exclude` edges and imports connect the dictionary clusters. Referenced terms that none of
+// the files define get a dashed node in the dictionary they are referenced from. A focus keeps
+// one term or struct and everything it transitively references.
+
+use std::collections::HashSet;
+use std::fmt::Write;
+
+use crate::types::{DataLangFile, DataLangItem, ParseError};
+use crate::{find_dictionary, quoted, resolve_dictionary};
+
+/// Diagram language to generate
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Format {
+    /// Graphviz DOT, for `dot -Tsvg`
+    #[default]
+    Dot,
+    /// A Mermaid flowchart, rendered by GitHub and most Markdown viewers
+    Mermaid,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum NodeKind {
+    SimpleTerm,
+    CompositeTerm,
+    StateMachine,
+    Struct,
+    /// Referenced but not defined by any of the files
+    External,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum EdgeKind {
+    Has,
+    Include,
+    Exclude,
+}
+
+impl EdgeKind {
+    fn label(self) -> &'static str {
+        match self {
+            EdgeKind::Has => "has",
+            EdgeKind::Include => "include",
+            EdgeKind::Exclude => "exclude",
+        }
+    }
+}
+
+/// A node, identified by its dictionary and name
+type Key = (String, String);
+
+struct Graph {
+    /// Dictionaries in the order their clusters are drawn
+    clusters: Vec<String>,
+    nodes: Vec<(Key, NodeKind)>,
+    edges: Vec<(Key, Key, EdgeKind)>,
+    imports: Vec<(String, String)>,
+}
+
+/// Draw a set of validated definition files, each paired with the name of the dictionary it
+/// defines. With a `focus` of `Name` or `Dictionary::Name`, only that term or struct and its
+/// transitive references are drawn.
+pub fn generate(
+    dictionaries: &[(String, DataLangFile)],
+    format: Format,
+    focus: Option<&str>,
+) -> Result<String, ParseError> {
+    let mut graph = Graph::new(dictionaries);
+    if let Some(focus) = focus {
+        let key = graph.find(focus)?;
+        graph.retain_reachable(&key);
+    }
+    Ok(match format {
+        Format::Dot => graph.to_dot(),
+        Format::Mermaid => graph.to_mermaid(),
+    })
+}
+
+impl Graph {
+    fn new(dictionaries: &[(String, DataLangFile)]) -> Self {
+        let mut graph = Graph {
+            clusters: dictionaries
+                .iter()
+                .map(|(dictionary, _)| dictionary.clone())
+                .collect(),
+            nodes: Vec::new(),
+            edges: Vec::new(),
+            imports: Vec::new(),
+        };
+
+        for (dictionary, file) in dictionaries {
+            for item in &file.items {
+                let (name, kind) = match item {
+                    DataLangItem::Term { name, .. } if file.is_simple_term(name) => {
+                        (name, NodeKind::SimpleTerm)
+                    }
+                    DataLangItem::Term { name, .. } => (name, NodeKind::CompositeTerm),
+                    DataLangItem::Struct { name, .. } => (name, NodeKind::Struct),
+                    DataLangItem::States { name, .. } => (name, NodeKind::StateMachine),
+                    DataLangItem::Import { module } => {
+                        graph.imports.push((dictionary.clone(), module.clone()));
+                        continue;
+                    }
+                    DataLangItem::Dictionary { .. } => continue,
+                };
+                graph.nodes.push(((dictionary.clone(), name.clone()), kind));
+            }
+        }
+
+        for (dictionary, file) in dictionaries {
+            for item in &file.items {
+                let (name, fields, included) = match item {
+                    DataLangItem::Term { name, fields, .. } => (name, fields, EdgeKind::Has),
+                    DataLangItem::Struct { name, fields, .. } => (name, fields, EdgeKind::Include),
+                    _ => continue,
+                };
+                for reference in fields {
+                    let target = (
+                        resolve_dictionary(
+                            dictionaries,
+                            dictionary,
+                            reference.namespace(),
+                            &reference.name,
+                        ),
+                        reference.name.clone(),
+                    );
+                    if find_dictionary(dictionaries, &target.0)
+                        .is_none_or(|file| file.get_fields(&target.1).is_none())
+                        && !graph.nodes.iter().any(|(key, _)| *key == target)
+                    {
+                        if !graph.clusters.contains(&target.0) {
+                            graph.clusters.push(target.0.clone());
+                        }
+                        graph.nodes.push((target.clone(), NodeKind::External));
+                    }
+                    let kind = if reference.is_included {
+                        included
+                    } else {
+                        EdgeKind::Exclude
+                    };
+                    graph
+                        .edges
+                        .push(((dictionary.clone(), name.clone()), target, kind));
+                }
+            }
+        }
+        graph
+    }
+
+    /// The node a `Name` or `Dictionary::Name` focus refers to
+    fn find(&self, focus: &str) -> Result<Key, ParseError> {
+        let (dictionary, name) = match focus.split_once("::") {
+            Some((dictionary, name)) => (Some(dictionary), name),
+            None => (None, focus),
+        };
+        let matches: Vec<&Key> = self
+            .nodes
+            .iter()
+            .map(|(key, _)| key)
+            .filter(|(node_dictionary, node_name)| {
+                node_name == name
+                    && dictionary.is_none_or(|dictionary| dictionary == node_dictionary)
+            })
+            .collect();
+        match matches.as_slice() {
+            [key] => Ok((*key).clone()),
+            [] => Err(ParseError::StructuralError {
+                context: focus.to_string(),
+                issue: "no term or struct with this name is defined".to_string(),
+            }),
+            _ => Err(ParseError::StructuralError {
+                context: focus.to_string(),
+                issue: "defined in more than one dictionary, focus on Dictionary::Name".to_string(),
+            }),
+        }
+    }
+
+    /// Keep the node and everything reachable from it, and the imports between what is left
+    fn retain_reachable(&mut self, start: &Key) {
+        let mut reached: HashSet<Key> = HashSet::from([start.clone()]);
+        let mut pending = vec![start.clone()];
+        while let Some(key) = pending.pop() {
+            for (from, to, _) in &self.edges {
+                if *from == key && reached.insert(to.clone()) {
+                    pending.push(to.clone());
+                }
+            }
+        }
+
+        self.nodes.retain(|(key, _)| reached.contains(key));
+        self.edges.retain(|(from, _, _)| reached.contains(from));
+        let nodes = &self.nodes;
+        self.clusters.retain(|cluster| {
+            nodes
+                .iter()
+                .any(|((dictionary, _), _)| dictionary == cluster)
+        });
+        let clusters = &self.clusters;
+        self.imports
+            .retain(|(from, to)| clusters.contains(from) && clusters.contains(to));
+    }
+
+    fn cluster_nodes<'a>(&'a self, cluster: &'a str) -> impl Iterator<Item = &'a (Key, NodeKind)> {
+        self.nodes
+            .iter()
+            .filter(move |((dictionary, _), _)| dictionary == cluster)
+    }
+
+    fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph DataLang {\n    rankdir=LR;\n    compound=true;\n");
+        for (index, cluster) in self.clusters.iter().enumerate() {
+            writeln!(dot, "    subgraph cluster_{index} {{").unwrap();
+            writeln!(dot, "        label={};", quoted(cluster)).unwrap();
+            for (key, kind) in self.cluster_nodes(cluster) {
+                let shape = match kind {
+                    NodeKind::SimpleTerm => "shape=ellipse",
+                    NodeKind::CompositeTerm => "shape=box, style=rounded",
+                    NodeKind::StateMachine => "shape=hexagon",
+                    NodeKind::Struct => "shape=box",
+                    NodeKind::External => "shape=ellipse, style=dashed",
+                };
+                writeln!(
+                    dot,
+                    "        {} [label={}, {shape}];",
+                    dot_id(key),
+                    quoted(&key.1)
+                )
+                .unwrap();
+            }
+            dot.push_str("    }\n");
+        }
+        for (from, to, kind) in &self.edges {
+            let style = if *kind == EdgeKind::Exclude {
+                ", style=dashed"
+            } else {
+                ""
+            };
+            writeln!(
+                dot,
+                "    {} -> {} [label={}{style}];",
+                dot_id(from),
+                dot_id(to),
+                quoted(kind.label())
+            )
+            .unwrap();
+        }
+        // Graphviz only connects clusters through nodes, clipped to the cluster borders
+        for (from, to) in &self.imports {
+            let (Some(tail), Some(head)) = (self.cluster_index(from), self.cluster_index(to))
+            else {
+                continue;
+            };
+            let (Some((from_node, _)), Some((to_node, _))) = (
+                self.cluster_nodes(from).next(),
+                self.cluster_nodes(to).next(),
+            ) else {
+                continue;
+            };
+            writeln!(
+                dot,
+                "    {} -> {} [label=\"imports\", style=bold, ltail=cluster_{tail}, lhead=cluster_{head}];",
+                dot_id(from_node),
+                dot_id(to_node)
+            )
+            .unwrap();
+        }
+        dot.push_str("}\n");
+        dot
+    }
+
+    fn to_mermaid(&self) -> String {
+        let mut mermaid = String::from("flowchart LR\n");
+        for (index, cluster) in self.clusters.iter().enumerate() {
+            writeln!(mermaid, "    subgraph d{index} [{}]", quoted(cluster)).unwrap();
+            for (key, kind) in self.cluster_nodes(cluster) {
+                let label = quoted(&key.1);
+                let node = match kind {
+                    NodeKind::SimpleTerm => format!("({label})"),
+                    NodeKind::CompositeTerm => format!("([{label}])"),
+                    NodeKind::StateMachine => format!("{{{{{label}}}}}"),
+                    NodeKind::Struct => format!("[{label}]"),
+                    NodeKind::External => format!("({label}):::external"),
+                };
+                writeln!(mermaid, "        {}{node}", self.mermaid_id(key)).unwrap();
+            }
+            mermaid.push_str("    end\n");
+        }
+        for (from, to, kind) in &self.edges {
+            let arrow = if *kind == EdgeKind::Exclude {
+                "-.->"
+            } else {
+                "-->"
+            };
+            writeln!(
+                mermaid,
+                "    {} {arrow}|{}| {}",
+                self.mermaid_id(from),
+                kind.label(),
+                self.mermaid_id(to)
+            )
+            .unwrap();
+        }
+        for (from, to) in &self.imports {
+            if let (Some(from), Some(to)) = (self.cluster_index(from), self.cluster_index(to)) {
+                writeln!(mermaid, "    d{from} ==>|imports| d{to}").unwrap();
+            }
+        }
+        if self
+            .nodes
+            .iter()
+            .any(|(_, kind)| *kind == NodeKind::External)
+        {
+            mermaid.push_str("    classDef external stroke-dasharray: 5 5\n");
+        }
+        mermaid
+    }
+
+    fn cluster_index(&self, dictionary: &str) -> Option<usize> {
+        self.clusters
+            .iter()
+            .position(|cluster| cluster == dictionary)
+    }
+
+    /// Mermaid ids cannot hold `::`, so nodes are numbered by cluster and position
+    fn mermaid_id(&self, key: &Key) -> String {
+        let cluster = self.cluster_index(&key.0).unwrap_or_default();
+        let position = self
+            .cluster_nodes(&key.0)
+            .position(|(node, _)| node == key)
+            .unwrap_or_default();
+        format!("d{cluster}n{position}")
+    }
+}
+
+/// Node ids are qualified, so terms with the same name in two dictionaries stay apart
+fn dot_id((dictionary, name): &Key) -> String {
+    quoted(&format!("{dictionary}::{name}"))
+}
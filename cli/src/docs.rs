@@ -0,0 +1,690 @@
+// Data dictionary documentation
+// Renders a set of definition files as a site with an index of dictionaries, a page per
+// dictionary and a page per term, state machine and struct. Pages of composite terms and structs
+// hold a table of the resolved fields with the dictionary each one comes from, and every page
+// lists the terms and structs using it across all the given files. Terms of dictionaries that
+// are part of the site are linked, so imports can be followed. Pages are built as blocks and
+// rendered as Markdown or HTML in definition order, so unchanged input gives identical output.
+
+use std::collections::HashMap;
+use std::fmt::Write;
+
+use crate::types::{DataLangFile, DataLangItem, ParseError, ResolvedField};
+use crate::{defines, find_dictionary, resolve_dictionary};
+
+/// Format of the generated pages
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Format {
+    /// GitHub-flavored Markdown
+    #[default]
+    Markdown,
+    /// Standalone HTML pages, with no assets to copy
+    Html,
+}
+
+impl Format {
+    /// Extension of the page files
+    pub fn extension(self) -> &'static str {
+        match self {
+            Format::Markdown => "md",
+            Format::Html => "html",
+        }
+    }
+}
+
+/// A generated page
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Page {
+    /// Path relative to the root of the site, with `/` separators
+    pub path: String,
+    pub content: String,
+}
+
+/// Path of the page of a dictionary, `Base/index.md`
+pub fn dictionary_path(dictionary: &str, format: Format) -> String {
+    format!("{dictionary}/index.{}", format.extension())
+}
+
+/// Path of the page of a term or struct, `Base/User.md`
+pub fn item_path(dictionary: &str, name: &str, format: Format) -> String {
+    format!("{dictionary}/{name}.{}", format.extension())
+}
+
+/// Generate every page for a set of validated definition files, each paired with the name of
+/// the dictionary it defines (see `dictionary_name`)
+pub fn generate(
+    dictionaries: &[(String, DataLangFile)],
+    format: Format,
+) -> Result<Vec<Page>, ParseError> {
+    let site = Site::new(dictionaries, format)?;
+    let mut pages = vec![site.page(
+        format!("index.{}", format.extension()),
+        "Data dictionary",
+        site.index(),
+    )];
+    for (dictionary, file) in dictionaries {
+        pages.push(site.page(
+            dictionary_path(dictionary, format),
+            dictionary,
+            site.dictionary_page(dictionary, file),
+        ));
+        for item in &file.items {
+            match item {
+                DataLangItem::Term { name, .. }
+                | DataLangItem::Struct { name, .. }
+                | DataLangItem::States { name, .. } => {
+                    let blocks = site.item_page(dictionary, file, item)?;
+                    pages.push(site.page(item_path(dictionary, name, format), name, blocks));
+                }
+                DataLangItem::Dictionary { .. } | DataLangItem::Import { .. } => {}
+            }
+        }
+    }
+    Ok(pages)
+}
+
+enum Inline {
+    Text(String),
+    Code(String),
+    Strong(String),
+    Link(String, String),
+}
+
+enum Block {
+    Heading(usize, Vec<Inline>),
+    Paragraph(Vec<Inline>),
+    List(Vec<Vec<Inline>>),
+    Table(Vec<&'static str>, Vec<Vec<Vec<Inline>>>),
+}
+
+fn text(text: impl Into<String>) -> Inline {
+    Inline::Text(text.into())
+}
+
+/// Where a term or struct is used, keyed by the dictionary and name it is defined under
+#[derive(Default)]
+struct Usage {
+    /// Terms and structs with a `+` (true) or `-` (false) reference to it
+    references: Vec<(String, String, bool)>,
+    /// Composite terms and structs holding it as a field once expanded
+    fields: Vec<(String, String)>,
+}
+
+struct Site<'a> {
+    dictionaries: &'a [(String, DataLangFile)],
+    format: Format,
+    usages: HashMap<(String, String), Usage>,
+}
+
+impl<'a> Site<'a> {
+    fn new(dictionaries: &'a [(String, DataLangFile)], format: Format) -> Result<Self, ParseError> {
+        let mut site = Site {
+            dictionaries,
+            format,
+            usages: HashMap::new(),
+        };
+        let mut usages: HashMap<(String, String), Usage> = HashMap::new();
+        for (dictionary, file) in dictionaries {
+            for item in &file.items {
+                let (DataLangItem::Term { name, fields, .. }
+                | DataLangItem::Struct { name, fields, .. }) = item
+                else {
+                    continue;
+                };
+                for reference in fields {
+                    let target = site.resolve(dictionary, reference.namespace(), &reference.name);
+                    usages
+                        .entry((target, reference.name.clone()))
+                        .or_default()
+                        .references
+                        .push((dictionary.clone(), name.clone(), reference.is_included));
+                }
+                if file.is_simple_term(name) {
+                    continue;
+                }
+                for field in file.expand_fields(name)? {
+                    let target = site.field_dictionary(dictionary, &field);
+                    usages
+                        .entry((target, field.term))
+                        .or_default()
+                        .fields
+                        .push((dictionary.clone(), name.clone()));
+                }
+            }
+        }
+        site.usages = usages;
+        Ok(site)
+    }
+
+    fn find(&self, dictionary: &str) -> Option<&'a DataLangFile> {
+        find_dictionary(self.dictionaries, dictionary)
+    }
+
+    fn defines(&self, dictionary: &str, name: &str) -> bool {
+        defines(self.dictionaries, dictionary, name)
+    }
+
+    fn resolve(&self, dictionary: &str, namespace: Option<&str>, name: &str) -> String {
+        resolve_dictionary(self.dictionaries, dictionary, namespace, name)
+    }
+
+    /// Files without a `dictionary` line resolve their own terms without a dictionary name
+    fn field_dictionary(&self, dictionary: &str, field: &ResolvedField) -> String {
+        match &field.dictionary {
+            Some(origin) => origin.clone(),
+            None => self.resolve(dictionary, None, &field.term),
+        }
+    }
+
+    /// Relative link from a page in the `from` dictionary, or from the root when `None`
+    fn href(&self, from: Option<&str>, dictionary: &str, file_name: &str) -> String {
+        match from {
+            Some(from) if from == dictionary => file_name.to_string(),
+            Some(_) => format!("../{dictionary}/{file_name}"),
+            None => format!("{dictionary}/{file_name}"),
+        }
+    }
+
+    fn dictionary_link(&self, from: Option<&str>, dictionary: &str) -> Inline {
+        if self.find(dictionary).is_none() {
+            return Inline::Code(dictionary.to_string());
+        }
+        let file_name = format!("index.{}", self.format.extension());
+        Inline::Link(
+            dictionary.to_string(),
+            self.href(from, dictionary, &file_name),
+        )
+    }
+
+    /// Link to a term or struct, or its name alone when it is not part of the site
+    fn item_link(&self, from: &str, dictionary: &str, name: &str) -> Inline {
+        if !self.defines(dictionary, name) {
+            return Inline::Code(name.to_string());
+        }
+        let file_name = format!("{name}.{}", self.format.extension());
+        Inline::Link(
+            name.to_string(),
+            self.href(Some(from), dictionary, &file_name),
+        )
+    }
+
+    fn page(&self, path: String, title: &str, blocks: Vec<Block>) -> Page {
+        let content = match self.format {
+            Format::Markdown => markdown(&blocks),
+            Format::Html => html(title, &blocks),
+        };
+        Page { path, content }
+    }
+
+    fn index(&self) -> Vec<Block> {
+        let rows = self
+            .dictionaries
+            .iter()
+            .map(|(dictionary, file)| {
+                let terms = file.items_by_type("term").len();
+                let structs = file.items_by_type("struct").len();
+                let imports: Vec<Inline> = file
+                    .imports()
+                    .map(|import| self.dictionary_link(None, import))
+                    .collect();
+                vec![
+                    vec![self.dictionary_link(None, dictionary)],
+                    vec![text(terms.to_string())],
+                    vec![text(structs.to_string())],
+                    separated(imports),
+                ]
+            })
+            .collect();
+        vec![
+            Block::Heading(1, vec![text("Data dictionary")]),
+            Block::Table(vec!["Dictionary", "Terms", "Structs", "Imports"], rows),
+        ]
+    }
+
+    fn dictionary_page(&self, dictionary: &str, file: &DataLangFile) -> Vec<Block> {
+        let mut blocks = vec![
+            Block::Paragraph(vec![self.root_link()]),
+            Block::Heading(1, vec![text(dictionary)]),
+        ];
+        if let Some(version) = file.version() {
+            blocks.push(Block::Paragraph(vec![text(format!("Version {version}"))]));
+        }
+
+        let imports: Vec<Inline> = file
+            .imports()
+            .map(|import| self.dictionary_link(Some(dictionary), import))
+            .collect();
+        if !imports.is_empty() {
+            let mut line = vec![text("Imports ")];
+            line.extend(separated(imports));
+            blocks.push(Block::Paragraph(line));
+        }
+        let importers: Vec<Inline> = self
+            .dictionaries
+            .iter()
+            .filter(|(_, other)| other.imports().any(|import| import == dictionary))
+            .map(|(other, _)| self.dictionary_link(Some(dictionary), other))
+            .collect();
+        if !importers.is_empty() {
+            let mut line = vec![text("Imported by ")];
+            line.extend(separated(importers));
+            blocks.push(Block::Paragraph(line));
+        }
+
+        let rows: Vec<Vec<Vec<Inline>>> = file
+            .items
+            .iter()
+            .filter_map(|item| match item {
+                DataLangItem::Term { name, .. }
+                | DataLangItem::Struct { name, .. }
+                | DataLangItem::States { name, .. } => Some(name),
+                DataLangItem::Dictionary { .. } | DataLangItem::Import { .. } => None,
+            })
+            .map(|name| {
+                let mut summary = Vec::new();
+                if let Some(line) = file.doc(name).and_then(|doc| doc.lines().next()) {
+                    summary.push(text(line));
+                }
+                if file.deprecation(name).is_some() {
+                    if !summary.is_empty() {
+                        summary.push(text(" "));
+                    }
+                    summary.push(Inline::Strong("Deprecated".to_string()));
+                }
+                vec![
+                    vec![self.item_link(dictionary, dictionary, name)],
+                    vec![text(kind(file, name))],
+                    summary,
+                ]
+            })
+            .collect();
+        if !rows.is_empty() {
+            blocks.push(Block::Heading(2, vec![text("Terms and structs")]));
+            blocks.push(Block::Table(vec!["Name", "Kind", "Description"], rows));
+        }
+        blocks
+    }
+
+    fn item_page(
+        &self,
+        dictionary: &str,
+        file: &DataLangFile,
+        item: &DataLangItem,
+    ) -> Result<Vec<Block>, ParseError> {
+        let (DataLangItem::Term { name, .. }
+        | DataLangItem::Struct { name, .. }
+        | DataLangItem::States { name, .. }) = item
+        else {
+            return Ok(Vec::new());
+        };
+
+        let mut blocks = vec![
+            Block::Paragraph(vec![
+                self.root_link(),
+                text(" / "),
+                self.dictionary_link(Some(dictionary), dictionary),
+            ]),
+            Block::Heading(1, vec![text(name)]),
+            Block::Paragraph(vec![
+                Inline::Strong(kind(file, name).to_string()),
+                text(" in the "),
+                self.dictionary_link(Some(dictionary), dictionary),
+                text(" dictionary"),
+            ]),
+        ];
+        for line in file.doc(name).into_iter().flat_map(str::lines) {
+            blocks.push(Block::Paragraph(vec![text(line)]));
+        }
+        if let Some(deprecation) = file.deprecation(name) {
+            let mut line = vec![
+                Inline::Strong("Deprecated:".to_string()),
+                text(format!(" {}", deprecation.note)),
+            ];
+            if let Some(replacement) = &deprecation.replacement {
+                line.push(text(" Use "));
+                line.push(self.item_link(dictionary, dictionary, replacement));
+                line.push(text(" instead."));
+            }
+            blocks.push(Block::Paragraph(line));
+        }
+
+        match item {
+            DataLangItem::States {
+                states,
+                transitions,
+                ..
+            } => {
+                blocks.push(Block::Heading(2, vec![text("States")]));
+                blocks.push(Block::List(
+                    states
+                        .iter()
+                        .enumerate()
+                        .map(|(index, state)| {
+                            let mut line = vec![Inline::Code(state.clone())];
+                            if index == 0 {
+                                line.push(text(" (initial)"));
+                            }
+                            line
+                        })
+                        .collect(),
+                ));
+                blocks.push(Block::Heading(2, vec![text("Transitions")]));
+                blocks.push(Block::Table(
+                    vec!["From", "To"],
+                    transitions
+                        .iter()
+                        .map(|(from, to)| {
+                            vec![
+                                vec![Inline::Code(from.clone())],
+                                vec![Inline::Code(to.clone())],
+                            ]
+                        })
+                        .collect(),
+                ));
+            }
+            _ if file.is_simple_term(name) => {
+                let rust_type = file.expand_fields(name)?[0].rust_type();
+                blocks.push(Block::Paragraph(vec![
+                    text("Values are stored as "),
+                    Inline::Code(rust_type.to_string()),
+                ]));
+            }
+            DataLangItem::Term { fields, .. } | DataLangItem::Struct { fields, .. } => {
+                blocks.push(Block::Heading(2, vec![text("Composition")]));
+                blocks.push(Block::List(
+                    fields
+                        .iter()
+                        .map(|reference| {
+                            let target =
+                                self.resolve(dictionary, reference.namespace(), &reference.name);
+                            let mut line = vec![
+                                text(if reference.is_included {
+                                    "Includes "
+                                } else {
+                                    "Excludes "
+                                }),
+                                self.item_link(dictionary, &target, &reference.name),
+                            ];
+                            if target != dictionary {
+                                line.push(text(" from "));
+                                line.push(self.dictionary_link(Some(dictionary), &target));
+                            }
+                            if reference.is_private {
+                                line.push(text(" (private)"));
+                            }
+                            line
+                        })
+                        .collect(),
+                ));
+                blocks.push(Block::Heading(2, vec![text("Fields")]));
+                blocks.push(self.field_table(dictionary, file, &file.expand_fields(name)?));
+            }
+            DataLangItem::Dictionary { .. } | DataLangItem::Import { .. } => {}
+        }
+
+        blocks.push(Block::Heading(2, vec![text("Used by")]));
+        let usage = self.usages.get(&(dictionary.to_string(), name.clone()));
+        let references = usage.map_or(&[][..], |usage| &usage.references[..]);
+        let holders = usage.map_or(&[][..], |usage| &usage.fields[..]);
+        if references.is_empty() && holders.is_empty() {
+            blocks.push(Block::Paragraph(vec![text(
+                "Not used by any term or struct.",
+            )]));
+        }
+        if !references.is_empty() {
+            blocks.push(Block::Paragraph(vec![text("Referenced by")]));
+            blocks.push(Block::List(
+                references
+                    .iter()
+                    .map(|(other, user, included)| {
+                        let mut line = self.usage_line(dictionary, other, user);
+                        line.push(text(if *included {
+                            ", included"
+                        } else {
+                            ", excluded"
+                        }));
+                        line
+                    })
+                    .collect(),
+            ));
+        }
+        if !holders.is_empty() {
+            blocks.push(Block::Paragraph(vec![text("Field of")]));
+            blocks.push(Block::List(
+                holders
+                    .iter()
+                    .map(|(other, holder)| self.usage_line(dictionary, other, holder))
+                    .collect(),
+            ));
+        }
+        Ok(blocks)
+    }
+
+    /// One row per resolved field, with a `Since` column in versioned dictionaries
+    fn field_table(
+        &self,
+        dictionary: &str,
+        file: &DataLangFile,
+        fields: &[ResolvedField],
+    ) -> Block {
+        let mut header = vec!["Field", "Term", "Dictionary", "Default", "Description"];
+        if file.version().is_some() {
+            header.insert(3, "Since");
+        }
+        let rows = fields
+            .iter()
+            .map(|field| {
+                let origin = self.field_dictionary(dictionary, field);
+                let mut name = vec![Inline::Code(field.field_name())];
+                if field.is_private {
+                    name.push(text(" (private)"));
+                }
+                let mut description = Vec::new();
+                // Terms from other files are not expanded there, so their doc comes from here
+                let doc = field.doc.as_deref().or_else(|| {
+                    self.find(&origin)
+                        .and_then(|origin_file| origin_file.doc(&field.term))
+                });
+                if let Some(doc) = doc {
+                    description.push(text(doc.lines().collect::<Vec<_>>().join(" ")));
+                }
+                if let Some(deprecation) = &field.deprecated {
+                    if !description.is_empty() {
+                        description.push(text(" "));
+                    }
+                    description.push(Inline::Strong("Deprecated:".to_string()));
+                    description.push(text(format!(" {}", deprecation.note)));
+                }
+                let mut row = vec![
+                    name,
+                    vec![self.item_link(dictionary, &origin, &field.term)],
+                    vec![self.dictionary_link(Some(dictionary), &origin)],
+                    field
+                        .default
+                        .iter()
+                        .map(|default| Inline::Code(default.clone()))
+                        .collect(),
+                    description,
+                ];
+                if file.version().is_some() {
+                    row.insert(3, vec![text(field.since.unwrap_or(1).to_string())]);
+                }
+                row
+            })
+            .collect();
+        Block::Table(header, rows)
+    }
+
+    fn usage_line(&self, from: &str, dictionary: &str, name: &str) -> Vec<Inline> {
+        let mut line = vec![self.item_link(from, dictionary, name)];
+        if dictionary != from {
+            line.push(text(" in "));
+            line.push(self.dictionary_link(Some(from), dictionary));
+        }
+        line
+    }
+
+    fn root_link(&self) -> Inline {
+        Inline::Link(
+            "Data dictionary".to_string(),
+            format!("../index.{}", self.format.extension()),
+        )
+    }
+}
+
+fn kind(file: &DataLangFile, name: &str) -> &'static str {
+    if file.state_machine(name).is_some() {
+        "State machine"
+    } else if file.structs().any(|(item, _)| item == name) {
+        "Struct"
+    } else if file.is_simple_term(name) {
+        "Simple term"
+    } else {
+        "Composite term"
+    }
+}
+
+/// Inlines joined with `, `
+fn separated(inlines: Vec<Inline>) -> Vec<Inline> {
+    let mut joined = Vec::new();
+    for inline in inlines {
+        if !joined.is_empty() {
+            joined.push(text(", "));
+        }
+        joined.push(inline);
+    }
+    joined
+}
+
+fn markdown(blocks: &[Block]) -> String {
+    let mut out = String::new();
+    for block in blocks {
+        if !out.is_empty() {
+            out.push('\n');
+        }
+        match block {
+            Block::Heading(level, inlines) => {
+                writeln!(out, "{} {}", "#".repeat(*level), markdown_inlines(inlines)).unwrap()
+            }
+            Block::Paragraph(inlines) => writeln!(out, "{}", markdown_inlines(inlines)).unwrap(),
+            Block::List(items) => {
+                for item in items {
+                    writeln!(out, "- {}", markdown_inlines(item)).unwrap();
+                }
+            }
+            Block::Table(header, rows) => {
+                writeln!(out, "| {} |", header.join(" | ")).unwrap();
+                writeln!(out, "|{}", " --- |".repeat(header.len())).unwrap();
+                for row in rows {
+                    let cells: Vec<String> =
+                        row.iter().map(|cell| markdown_inlines(cell)).collect();
+                    writeln!(out, "| {} |", cells.join(" | ")).unwrap();
+                }
+            }
+        }
+    }
+    out
+}
+
+fn markdown_inlines(inlines: &[Inline]) -> String {
+    inlines
+        .iter()
+        .map(|inline| match inline {
+            Inline::Text(text) => escape_markdown(text),
+            Inline::Code(code) => {
+                let code = code.replace('|', "\\|");
+                if code.contains('`') {
+                    format!("`` {code} ``")
+                } else {
+                    format!("`{code}`")
+                }
+            }
+            Inline::Strong(text) => format!("**{}**", escape_markdown(text)),
+            Inline::Link(text, href) => format!("[{}]({href})", escape_markdown(text)),
+        })
+        .collect()
+}
+
+fn escape_markdown(text: &str) -> String {
+    let mut escaped = String::new();
+    for c in text.chars() {
+        match c {
+            '\\' | '`' | '*' | '_' | '[' | ']' | '<' | '>' | '|' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            '\n' => escaped.push(' '),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn html(title: &str, blocks: &[Block]) -> String {
+    let mut out =
+        String::from("<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n");
+    writeln!(out, "<title>{}</title>", escape_html(title)).unwrap();
+    out.push_str(
+        "<style>\nbody { font-family: sans-serif; max-width: 60rem; margin: 2rem auto; padding: 0 1rem; }\n\
+         table { border-collapse: collapse; }\n\
+         th, td { border: 1px solid #ccc; padding: 0.25rem 0.5rem; text-align: left; }\n\
+         </style>\n</head>\n<body>\n",
+    );
+    for block in blocks {
+        match block {
+            Block::Heading(level, inlines) => {
+                writeln!(out, "<h{level}>{}</h{level}>", html_inlines(inlines)).unwrap()
+            }
+            Block::Paragraph(inlines) => writeln!(out, "<p>{}</p>", html_inlines(inlines)).unwrap(),
+            Block::List(items) => {
+                out.push_str("<ul>\n");
+                for item in items {
+                    writeln!(out, "<li>{}</li>", html_inlines(item)).unwrap();
+                }
+                out.push_str("</ul>\n");
+            }
+            Block::Table(header, rows) => {
+                out.push_str("<table>\n<thead>\n<tr>");
+                for cell in header {
+                    write!(out, "<th>{cell}</th>").unwrap();
+                }
+                out.push_str("</tr>\n</thead>\n<tbody>\n");
+                for row in rows {
+                    out.push_str("<tr>");
+                    for cell in row {
+                        write!(out, "<td>{}</td>", html_inlines(cell)).unwrap();
+                    }
+                    out.push_str("</tr>\n");
+                }
+                out.push_str("</tbody>\n</table>\n");
+            }
+        }
+    }
+    out.push_str("</body>\n</html>\n");
+    out
+}
+
+fn html_inlines(inlines: &[Inline]) -> String {
+    inlines
+        .iter()
+        .map(|inline| match inline {
+            Inline::Text(text) => escape_html(text),
+            Inline::Code(code) => format!("<code>{}</code>", escape_html(code)),
+            Inline::Strong(text) => format!("<strong>{}</strong>", escape_html(text)),
+            Inline::Link(text, href) => {
+                format!(
+                    "<a href=\"{}\">{}</a>",
+                    escape_html(href),
+                    escape_html(text)
+                )
+            }
+        })
+        .collect()
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
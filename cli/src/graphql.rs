@@ -0,0 +1,161 @@
+// GraphQL SDL export
+// Composite terms and structs become object types and state machine terms become enums.
+// Composite terms of the same file are fields of their object type, so `+User` gives a
+// `user: User!` field. A composite with some of its terms removed by `-` references cannot be
+// referenced as a whole, so its remaining terms become fields of the including type. Simple
+// terms and terms from other dictionaries hold a `String`, like the fields generated in Rust.
+// Fields that data may lack, those with `@default`, `@since` or `@removed`, are nullable.
+
+use std::fmt::Write;
+
+use crate::quoted;
+use crate::types::{
+    DataLangFile, DataLangItem, Deprecation, FieldReference, ParseError, ResolvedField, snake_case,
+};
+
+/// A field of an object type
+struct Member {
+    name: String,
+    /// `String` or the object type of a composite term
+    ty: String,
+    optional: bool,
+    doc: Option<String>,
+    deprecated: Option<Deprecation>,
+}
+
+impl Member {
+    fn scalar(field: &ResolvedField) -> Self {
+        Member {
+            name: field.field_name(),
+            ty: "String".to_string(),
+            optional: field.is_optional(),
+            doc: field.doc.clone(),
+            deprecated: field.deprecated.clone(),
+        }
+    }
+
+    fn object(file: &DataLangFile, reference: &FieldReference) -> Self {
+        let name = &reference.name;
+        Member {
+            name: name.to_lowercase(),
+            ty: name.clone(),
+            optional: ["default", "since", "removed"]
+                .iter()
+                .any(|annotation| reference.annotation(annotation).is_some()),
+            doc: reference
+                .annotation("doc")
+                .and_then(|annotation| annotation.args.first())
+                .map(String::as_str)
+                .or(file.doc(name))
+                .map(str::to_string),
+            deprecated: reference
+                .annotation("deprecated")
+                .map(Deprecation::from_annotation)
+                .or_else(|| file.deprecation(name)),
+        }
+    }
+}
+
+/// Generate the SDL of a validated definition file
+pub fn generate(file: &DataLangFile) -> Result<String, ParseError> {
+    let mut out = String::from("# Generated by datalang from a DataLang definition file\n");
+    if let Some(dictionary) = file.dictionary_name() {
+        writeln!(out, "# Dictionary: {dictionary}").unwrap();
+    }
+
+    for item in &file.items {
+        match item {
+            DataLangItem::Term { name, .. } | DataLangItem::Struct { name, .. } => {
+                if file.is_simple_term(name) {
+                    continue;
+                }
+                let fields = file.expand_fields(name)?;
+                let mut members = Vec::new();
+                collect_members(file, name, &fields, &mut members)?;
+                out.push('\n');
+                write_type(&mut out, file, name, &members);
+            }
+            DataLangItem::States { name, states, .. } => {
+                out.push('\n');
+                write_enum(&mut out, file, name, states);
+            }
+            DataLangItem::Dictionary { .. } | DataLangItem::Import { .. } => {}
+        }
+    }
+    Ok(out)
+}
+
+/// The fields of an object type, given the terms `expand_fields` kept for it
+fn collect_members(
+    file: &DataLangFile,
+    name: &str,
+    fields: &[ResolvedField],
+    members: &mut Vec<Member>,
+) -> Result<(), ParseError> {
+    let kept = |term: &str| fields.iter().any(|field| field.term == term);
+
+    for reference in file.get_fields(name).unwrap_or_default() {
+        if !reference.is_included {
+            continue;
+        }
+        let is_local = reference
+            .namespace()
+            .is_none_or(|namespace| file.dictionary_name() == Some(namespace));
+        let is_composite = is_local
+            && file.get_fields(&reference.name).is_some()
+            && !file.is_simple_term(&reference.name);
+
+        if !is_composite {
+            if let Some(field) = fields.iter().find(|field| field.term == reference.name)
+                && !members
+                    .iter()
+                    .any(|member| member.name == field.field_name())
+            {
+                members.push(Member::scalar(field));
+            }
+            continue;
+        }
+
+        let terms = file.expand_fields(&reference.name)?;
+        if !terms.is_empty() && terms.iter().all(|term| kept(&term.term)) {
+            if !members.iter().any(|member| member.ty == reference.name) {
+                members.push(Member::object(file, reference));
+            }
+        } else {
+            collect_members(file, &reference.name, fields, members)?;
+        }
+    }
+    Ok(())
+}
+
+fn write_type(out: &mut String, file: &DataLangFile, name: &str, members: &[Member]) {
+    write_description(out, "", file.doc(name));
+    writeln!(out, "type {name} {{").unwrap();
+    for member in members {
+        write_description(out, "  ", member.doc.as_deref());
+        let required = if member.optional { "" } else { "!" };
+        write!(out, "  {}: {}{required}", member.name, member.ty).unwrap();
+        if let Some(deprecated) = &member.deprecated {
+            write!(out, " @deprecated(reason: {})", quoted(&deprecated.note)).unwrap();
+        }
+        out.push('\n');
+    }
+    out.push_str("}\n");
+}
+
+/// Values follow the GraphQL convention, `OutForDelivery` becomes `OUT_FOR_DELIVERY`
+fn write_enum(out: &mut String, file: &DataLangFile, name: &str, states: &[String]) {
+    write_description(out, "", file.doc(name));
+    writeln!(out, "enum {name} {{").unwrap();
+    for state in states {
+        writeln!(out, "  {}", snake_case(state).to_uppercase()).unwrap();
+    }
+    out.push_str("}\n");
+}
+
+fn write_description(out: &mut String, indent: &str, doc: Option<&str>) {
+    if let Some(doc) = doc {
+        let doc = doc.replace("\"\"\"", "\\\"\"\"");
+        writeln!(out, "{indent}\"\"\"{doc}\"\"\"").unwrap();
+    }
+}
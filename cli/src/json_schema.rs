@@ -0,0 +1,116 @@
+// JSON Schema 2020-12 export
+// Every term and struct of a definition file becomes an entry in `$defs`. Simple terms
+// are strings, state machine terms are string enums and composite terms and structs are
+// objects whose properties `$ref` the simple term they were expanded from. Terms from
+// other dictionaries are referenced in the `<Dictionary>.schema.json` file next to this one.
+
+use serde_json::{Map, Value, json};
+
+use crate::types::{DataLangFile, DataLangItem, ParseError, ResolvedField};
+
+/// Dialect every generated schema declares in `$schema`
+pub const DIALECT: &str = "https://json-schema.org/draft/2020-12/schema";
+
+/// File name of the schema generated for a dictionary, used for `$id` and external `$ref`s
+pub fn file_name(dictionary: &str) -> String {
+    format!("{dictionary}.schema.json")
+}
+
+/// Generate the schema of a validated definition file.
+/// Versioned dictionaries are exported at their latest version.
+pub fn generate(file: &DataLangFile) -> Result<Value, ParseError> {
+    let mut defs = Map::new();
+    for item in &file.items {
+        let (name, schema) = match item {
+            DataLangItem::Term { name, .. } | DataLangItem::Struct { name, .. } => {
+                let schema = if file.is_simple_term(name) {
+                    json!({ "type": "string" })
+                } else {
+                    object_schema(file, &file.expand_fields(name)?)
+                };
+                (name, schema)
+            }
+            DataLangItem::States { name, states, .. } => {
+                (name, json!({ "type": "string", "enum": states }))
+            }
+            DataLangItem::Dictionary { .. } | DataLangItem::Import { .. } => continue,
+        };
+        let mut schema = schema;
+        describe(
+            &mut schema,
+            file.doc(name),
+            file.deprecation(name).map(|d| d.note),
+        );
+        defs.insert(name.clone(), schema);
+    }
+
+    let mut root = Map::new();
+    root.insert("$schema".to_string(), json!(DIALECT));
+    if let Some(dictionary) = file.dictionary_name() {
+        root.insert("$id".to_string(), json!(file_name(dictionary)));
+        root.insert("title".to_string(), json!(dictionary));
+    }
+    root.insert("$defs".to_string(), Value::Object(defs));
+    Ok(Value::Object(root))
+}
+
+/// Generate the schema of a definition file as pretty-printed JSON
+pub fn to_string(file: &DataLangFile) -> Result<String, ParseError> {
+    let schema = generate(file)?;
+    Ok(serde_json::to_string_pretty(&schema).expect("schemas only hold strings and maps"))
+}
+
+/// An object with one property per field. Optional fields, those with a `@default`, `@since`
+/// or `@removed`, may be left out.
+fn object_schema(file: &DataLangFile, fields: &[ResolvedField]) -> Value {
+    let mut properties = Map::new();
+    let mut required = Vec::new();
+    for field in fields {
+        let mut property = json!({ "$ref": reference(file, field) });
+        // A term's own `@doc` is already on its definition
+        let doc = field
+            .doc
+            .as_deref()
+            .filter(|doc| file.doc(&field.term) != Some(*doc));
+        describe(
+            &mut property,
+            doc,
+            field.deprecated.as_ref().map(|d| d.note.clone()),
+        );
+        if let Some(default) = &field.default {
+            property["default"] = json!(default);
+        }
+        if !field.is_optional() {
+            required.push(field.field_name());
+        }
+        properties.insert(field.field_name(), property);
+    }
+
+    json!({
+        "type": "object",
+        "properties": properties,
+        "required": required,
+        "additionalProperties": false,
+    })
+}
+
+/// `$ref` to the definition of a field's term, in this file or its dictionary's file
+fn reference(file: &DataLangFile, field: &ResolvedField) -> String {
+    match field.dictionary.as_deref() {
+        Some(dictionary) if file.dictionary_name() != Some(dictionary) => {
+            format!("{}#/$defs/{}", file_name(dictionary), field.term)
+        }
+        _ => format!("#/$defs/{}", field.term),
+    }
+}
+
+/// Add `description` from `@doc` and `deprecated` from `@deprecated`, keeping its note
+fn describe(schema: &mut Value, doc: Option<&str>, deprecated: Option<String>) {
+    if let Some(doc) = doc {
+        schema["description"] = json!(doc);
+    }
+    if let Some(note) = deprecated {
+        schema["deprecated"] = json!(true);
+        schema["$comment"] = json!(format!("Deprecated: {note}"));
+    }
+}
@@ -0,0 +1,137 @@
+// Tooling for DataLang definition files
+// Shares the parser in `src/types.rs` with the build script and the macros, and adds
+// everything that works on whole definition files rather than on generated code.
+
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+
+#[path = "../../src/types.rs"]
+mod types;
+
+pub mod avro;
+pub mod compat;
+pub mod diagram;
+pub mod docs;
+pub mod graphql;
+pub mod json_schema;
+pub mod proto;
+pub mod python;
+pub mod sql;
+pub mod typescript;
+
+pub use types::{
+    Annotation, DataLangFile, DataLangItem, Deprecation, FieldReference, ParseError, ResolvedField,
+    Transition, Visibility,
+};
+
+/// Read, parse and validate a definition file
+pub fn load(path: impl AsRef<Path>) -> Result<DataLangFile, String> {
+    let path = path.as_ref();
+    let content = fs::read_to_string(path)
+        .map_err(|error| format!("Failed to read {}: {error}", path.display()))?;
+    let file = DataLangFile::parse_from_str(&content)
+        .map_err(|error| format!("Parse error in {}: {error}", path.display()))?;
+    file.validate()
+        .map_err(|error| format!("Validation error in {}: {error}", path.display()))?;
+    Ok(file)
+}
+
+/// Name of the dictionary a definition file defines, or its file stem when it has no
+/// `dictionary` line, matching the module the build script generates for it
+pub fn dictionary_name(path: impl AsRef<Path>, file: &DataLangFile) -> String {
+    match file.dictionary_name() {
+        Some(name) => name.to_string(),
+        None => path
+            .as_ref()
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_default(),
+    }
+}
+
+/// The file defining a dictionary, among files paired with their dictionary names
+pub(crate) fn find_dictionary<'a>(
+    dictionaries: &'a [(String, DataLangFile)],
+    dictionary: &str,
+) -> Option<&'a DataLangFile> {
+    dictionaries
+        .iter()
+        .find(|(name, _)| name == dictionary)
+        .map(|(_, file)| file)
+}
+
+/// Check whether a dictionary defines a term, state machine or struct
+pub(crate) fn defines(
+    dictionaries: &[(String, DataLangFile)],
+    dictionary: &str,
+    name: &str,
+) -> bool {
+    find_dictionary(dictionaries, dictionary)
+        .is_some_and(|file| file.get_fields(name).is_some() || file.state_machine(name).is_some())
+}
+
+/// Dictionary a reference from `dictionary` points into: its namespace, `dictionary` itself
+/// when the term is defined there, or the first import defining it
+pub(crate) fn resolve_dictionary(
+    dictionaries: &[(String, DataLangFile)],
+    dictionary: &str,
+    namespace: Option<&str>,
+    name: &str,
+) -> String {
+    if let Some(namespace) = namespace {
+        return namespace.to_string();
+    }
+    if defines(dictionaries, dictionary, name) {
+        return dictionary.to_string();
+    }
+    find_dictionary(dictionaries, dictionary)
+        .and_then(|file| {
+            file.imports()
+                .find(|import| defines(dictionaries, import, name))
+        })
+        .unwrap_or(dictionary)
+        .to_string()
+}
+
+/// Dictionary another module must provide a field's term from: its namespace when that is
+/// not this file's dictionary, or the import an unqualified term the file does not define
+/// comes from. Only the file itself is known here, so that import must be the only one.
+pub(crate) fn external_dictionary<'a>(
+    file: &'a DataLangFile,
+    field: &'a ResolvedField,
+) -> Result<Option<&'a str>, ParseError> {
+    if let Some(dictionary) = field.dictionary.as_deref() {
+        let is_own = file.dictionary_name() == Some(dictionary);
+        return Ok((!is_own).then_some(dictionary));
+    }
+    if file.get_fields(&field.term).is_some() {
+        return Ok(None);
+    }
+    let mut imports = file.imports();
+    match (imports.next(), imports.next()) {
+        (Some(import), None) => Ok(Some(import)),
+        (None, _) => Ok(None),
+        (Some(_), Some(_)) => Err(ParseError::InvalidFieldReference {
+            field: field.term.clone(),
+            reason: "more than one dictionary is imported, qualify the term with the one \
+                     defining it"
+                .to_string(),
+        }),
+    }
+}
+
+/// A double-quoted string literal escaping `\` and `"`, valid in GraphQL, TypeScript, Python
+/// and DOT
+pub(crate) fn quoted(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Write a doc as line comments, each line starting with `prefix` such as `"  //"`
+pub(crate) fn write_comment(out: &mut String, prefix: &str, doc: Option<&str>) {
+    if let Some(doc) = doc {
+        for line in doc.lines() {
+            writeln!(out, "{prefix} {line}").unwrap();
+        }
+    }
+}
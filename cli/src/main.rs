@@ -0,0 +1,301 @@
+// Command line entry point for DataLang tooling
+//
+// Usage:
+//   datalang compat <old.txt> <new.txt> [--json]
+//   datalang json-schema <file.txt> [--out <file.schema.json>]
+//   datalang proto <file.txt> [--numbers <numbers.json>] [--out <file.proto>]
+//   datalang graphql <file.txt> [--out <file.graphql>]
+//   datalang sql <file.txt> [--dialect sqlite|postgres] [--out <file.sql>]
+//   datalang typescript <file.txt> [--declarations] [--out <file.ts>]
+//   datalang python <file.txt> [--pydantic] [--out <file.py>]
+//   datalang avro <file.txt> [--record <name>] [--out <file.avsc>]
+//   datalang docs <file.txt>... [--html] [--out <dir>]
+//   datalang diagram <file.txt>... [--mermaid] [--focus <name>] [--out <file.dot>]
+
+use std::process::ExitCode;
+
+use datalang_cli::{
+    avro, compat, diagram, dictionary_name, docs, graphql, json_schema, load, proto, python, sql,
+    typescript,
+};
+
+const USAGE: &str = "Usage:
+  datalang compat <old> <new> [--json]
+      Classify the changes between two definition files
+  datalang json-schema <file> [--out <path>]
+      Export a definition file as JSON Schema 2020-12
+  datalang proto <file> [--numbers <path>] [--out <path>]
+      Export a definition file as proto3, keeping field numbers in the --numbers file
+  datalang graphql <file> [--out <path>]
+      Export a definition file as GraphQL SDL
+  datalang sql <file> [--dialect sqlite|postgres] [--out <path>]
+      Generate CREATE TABLE statements, for SQLite unless --dialect says otherwise
+  datalang typescript <file> [--declarations] [--out <path>]
+      Export a definition file as a TypeScript module, or a .d.ts file with --declarations
+  datalang python <file> [--pydantic] [--out <path>]
+      Export a definition file as a Python module of dataclasses, or pydantic models
+  datalang avro <file> [--record <name>] [--out <path>]
+      Export every record and enum as an Avro schema, or only the one given with --record
+  datalang docs <file>... [--html] [--out <dir>]
+      Write a Markdown (or HTML) page per dictionary and term into --out, docs by default
+  datalang diagram <file>... [--mermaid] [--focus <name>] [--out <path>]
+      Draw how dictionaries, terms and structs are composed as DOT, or Mermaid with --mermaid";
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+
+    let result = match args.first().map(String::as_str) {
+        Some("compat") => run_compat(&args[1..]),
+        Some("json-schema") => run_json_schema(&args[1..]),
+        Some("proto") => run_proto(&args[1..]),
+        Some("graphql") => run_graphql(&args[1..]),
+        Some("sql") => run_sql(&args[1..]),
+        Some("typescript") => run_typescript(&args[1..]),
+        Some("python") => run_python(&args[1..]),
+        Some("avro") => run_avro(&args[1..]),
+        Some("docs") => run_docs(&args[1..]),
+        Some("diagram") => run_diagram(&args[1..]),
+        Some("help" | "--help" | "-h") => {
+            println!("{USAGE}");
+            return ExitCode::SUCCESS;
+        }
+        _ => Err(USAGE.to_string()),
+    };
+
+    match result {
+        Ok(code) => code,
+        Err(error) => {
+            eprintln!("{error}");
+            ExitCode::from(2)
+        }
+    }
+}
+
+/// Exits with 1 when the change is breaking, so it can gate merges
+fn run_compat(args: &[String]) -> Result<ExitCode, String> {
+    let json = args.iter().any(|arg| arg == "--json");
+    let paths: Vec<&String> = args.iter().filter(|arg| *arg != "--json").collect();
+    let [old, new] = paths.as_slice() else {
+        return Err(USAGE.to_string());
+    };
+
+    let report = compat::check(&load(old)?, &load(new)?).map_err(|error| error.to_string())?;
+    if json {
+        println!("{}", report.to_json());
+    } else {
+        println!("{report}");
+    }
+
+    Ok(if report.is_breaking() {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    })
+}
+
+/// Remove `name <value>` from the arguments and return the value
+fn take_option(args: &mut Vec<String>, name: &str) -> Result<Option<String>, String> {
+    let Some(index) = args.iter().position(|arg| arg == name) else {
+        return Ok(None);
+    };
+    if index + 1 >= args.len() {
+        return Err(format!("{name} needs a value\n{USAGE}"));
+    }
+    args.remove(index);
+    Ok(Some(args.remove(index)))
+}
+
+/// Remove a `name` flag from the arguments and tell whether it was there
+fn take_flag(args: &mut Vec<String>, name: &str) -> bool {
+    let count = args.len();
+    args.retain(|arg| arg != name);
+    args.len() != count
+}
+
+/// Print generated text, or write it to the `--out` path
+fn write_output(text: &str, out: Option<&str>) -> Result<(), String> {
+    match out {
+        Some(out) => std::fs::write(out, format!("{}\n", text.trim_end()))
+            .map_err(|error| format!("Failed to write {out}: {error}")),
+        None => {
+            println!("{}", text.trim_end());
+            Ok(())
+        }
+    }
+}
+
+fn run_json_schema(args: &[String]) -> Result<ExitCode, String> {
+    let mut args = args.to_vec();
+    let out = take_option(&mut args, "--out")?;
+    let [input] = args.as_slice() else {
+        return Err(USAGE.to_string());
+    };
+
+    let schema = json_schema::to_string(&load(input)?).map_err(|error| error.to_string())?;
+    write_output(&schema, out.as_deref())?;
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Numbers for new fields are added to the `--numbers` file, which is created when missing
+fn run_proto(args: &[String]) -> Result<ExitCode, String> {
+    let mut args = args.to_vec();
+    let out = take_option(&mut args, "--out")?;
+    let numbers_path = take_option(&mut args, "--numbers")?;
+    let [input] = args.as_slice() else {
+        return Err(USAGE.to_string());
+    };
+
+    let mut numbers = match &numbers_path {
+        Some(path) if std::path::Path::new(path).exists() => {
+            let text = std::fs::read_to_string(path)
+                .map_err(|error| format!("Failed to read {path}: {error}"))?;
+            proto::FieldNumbers::from_json(&text).map_err(|error| format!("{path}: {error}"))?
+        }
+        _ => proto::FieldNumbers::default(),
+    };
+
+    let text = proto::generate(&load(input)?, &mut numbers).map_err(|error| error.to_string())?;
+    if let Some(path) = &numbers_path {
+        write_output(&numbers.to_json(), Some(path))?;
+    }
+    write_output(&text, out.as_deref())?;
+    Ok(ExitCode::SUCCESS)
+}
+
+fn run_graphql(args: &[String]) -> Result<ExitCode, String> {
+    let mut args = args.to_vec();
+    let out = take_option(&mut args, "--out")?;
+    let [input] = args.as_slice() else {
+        return Err(USAGE.to_string());
+    };
+
+    let sdl = graphql::generate(&load(input)?).map_err(|error| error.to_string())?;
+    write_output(&sdl, out.as_deref())?;
+    Ok(ExitCode::SUCCESS)
+}
+
+fn run_sql(args: &[String]) -> Result<ExitCode, String> {
+    let mut args = args.to_vec();
+    let out = take_option(&mut args, "--out")?;
+    let dialect = match take_option(&mut args, "--dialect")? {
+        Some(dialect) => dialect.parse()?,
+        None => sql::Dialect::default(),
+    };
+    let [input] = args.as_slice() else {
+        return Err(USAGE.to_string());
+    };
+
+    let ddl = sql::generate(&load(input)?, dialect).map_err(|error| error.to_string())?;
+    write_output(&ddl, out.as_deref())?;
+    Ok(ExitCode::SUCCESS)
+}
+
+fn run_typescript(args: &[String]) -> Result<ExitCode, String> {
+    let mut args = args.to_vec();
+    let out = take_option(&mut args, "--out")?;
+    let output = if take_flag(&mut args, "--declarations") {
+        typescript::Output::Declarations
+    } else {
+        typescript::Output::Module
+    };
+    let [input] = args.as_slice() else {
+        return Err(USAGE.to_string());
+    };
+
+    let module = typescript::generate(&load(input)?, output).map_err(|error| error.to_string())?;
+    write_output(&module, out.as_deref())?;
+    Ok(ExitCode::SUCCESS)
+}
+
+fn run_python(args: &[String]) -> Result<ExitCode, String> {
+    let mut args = args.to_vec();
+    let out = take_option(&mut args, "--out")?;
+    let style = if take_flag(&mut args, "--pydantic") {
+        python::Style::Pydantic
+    } else {
+        python::Style::Dataclass
+    };
+    let [input] = args.as_slice() else {
+        return Err(USAGE.to_string());
+    };
+
+    let module = python::generate(&load(input)?, style).map_err(|error| error.to_string())?;
+    write_output(&module, out.as_deref())?;
+    Ok(ExitCode::SUCCESS)
+}
+
+fn run_avro(args: &[String]) -> Result<ExitCode, String> {
+    let mut args = args.to_vec();
+    let out = take_option(&mut args, "--out")?;
+    let record = take_option(&mut args, "--record")?;
+    let [input] = args.as_slice() else {
+        return Err(USAGE.to_string());
+    };
+
+    let file = load(input)?;
+    let schema = match record {
+        Some(record) => avro::schema(&file, &record).map(|schema| {
+            serde_json::to_string_pretty(&schema).expect("schemas only hold strings and maps")
+        }),
+        None => avro::to_string(&file),
+    }
+    .map_err(|error| error.to_string())?;
+    write_output(&schema, out.as_deref())?;
+    Ok(ExitCode::SUCCESS)
+}
+
+/// All files are documented together, so usages and links span their dictionaries
+fn run_docs(args: &[String]) -> Result<ExitCode, String> {
+    let mut args = args.to_vec();
+    let out = take_option(&mut args, "--out")?.unwrap_or_else(|| "docs".to_string());
+    let format = if take_flag(&mut args, "--html") {
+        docs::Format::Html
+    } else {
+        docs::Format::Markdown
+    };
+    if args.is_empty() {
+        return Err(USAGE.to_string());
+    }
+
+    let mut dictionaries = Vec::new();
+    for input in &args {
+        let file = load(input)?;
+        dictionaries.push((dictionary_name(input, &file), file));
+    }
+    let pages = docs::generate(&dictionaries, format).map_err(|error| error.to_string())?;
+    for page in &pages {
+        let path = std::path::Path::new(&out).join(&page.path);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|error| format!("Failed to create {}: {error}", parent.display()))?;
+        }
+        write_output(&page.content, Some(&path.to_string_lossy()))?;
+    }
+    println!("Wrote {} pages to {out}", pages.len());
+    Ok(ExitCode::SUCCESS)
+}
+
+/// `--focus` takes `Name` or `Dictionary::Name` and keeps only what it transitively references
+fn run_diagram(args: &[String]) -> Result<ExitCode, String> {
+    let mut args = args.to_vec();
+    let out = take_option(&mut args, "--out")?;
+    let focus = take_option(&mut args, "--focus")?;
+    let format = if take_flag(&mut args, "--mermaid") {
+        diagram::Format::Mermaid
+    } else {
+        diagram::Format::Dot
+    };
+    if args.is_empty() {
+        return Err(USAGE.to_string());
+    }
+
+    let mut dictionaries = Vec::new();
+    for input in &args {
+        let file = load(input)?;
+        dictionaries.push((dictionary_name(input, &file), file));
+    }
+    let text = diagram::generate(&dictionaries, format, focus.as_deref())
+        .map_err(|error| error.to_string())?;
+    write_output(&text, out.as_deref())?;
+    Ok(ExitCode::SUCCESS)
+}
@@ -0,0 +1,237 @@
+// Protocol Buffers export
+// Composite terms and structs become proto3 messages, simple terms become `string` fields, like
+// the `String` they generate in Rust, and state machine terms become enums. Field and enum value numbers come
+// from a `FieldNumbers` file that is meant to be checked in next to the `.proto`: numbers
+// are only ever added to it, and a field that disappears keeps its number as `reserved`.
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+use serde::{Deserialize, Serialize};
+
+use crate::types::{DataLangFile, DataLangItem, ParseError, ResolvedField, snake_case};
+use crate::write_comment;
+
+/// Numbers between 19000 and 19999 are reserved by the protobuf implementation
+const IMPLEMENTATION_RESERVED: std::ops::RangeInclusive<u32> = 19000..=19999;
+
+/// Numbers handed out to the fields of one message or the values of one enum
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Allocation {
+    /// Numbers of the fields or values that currently exist
+    #[serde(default)]
+    pub numbers: BTreeMap<String, u32>,
+    /// Numbers of fields or values that were removed, which must never be reused
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub reserved: BTreeMap<String, u32>,
+}
+
+impl Allocation {
+    /// Give every name in `names` a number, reviving reserved names under their old number
+    fn allocate(&mut self, names: &[String], first: u32) {
+        for name in names {
+            if self.numbers.contains_key(name) {
+                continue;
+            }
+            let number = match self.reserved.remove(name) {
+                Some(number) => number,
+                None => self.next_number(first),
+            };
+            self.numbers.insert(name.clone(), number);
+        }
+    }
+
+    /// Move every name missing from `names` to the reserved numbers
+    fn reserve_missing(&mut self, names: &[String]) {
+        let removed: Vec<String> = self
+            .numbers
+            .keys()
+            .filter(|name| !names.contains(name))
+            .cloned()
+            .collect();
+        for name in removed {
+            if let Some(number) = self.numbers.remove(&name) {
+                self.reserved.insert(name, number);
+            }
+        }
+    }
+
+    fn next_number(&self, first: u32) -> u32 {
+        let used = self.numbers.values().chain(self.reserved.values());
+        let mut number = used
+            .map(|number| number + 1)
+            .max()
+            .unwrap_or(first)
+            .max(first);
+        if IMPLEMENTATION_RESERVED.contains(&number) {
+            number = IMPLEMENTATION_RESERVED.end() + 1;
+        }
+        number
+    }
+
+    /// `reserved 3, 5;` and `reserved "old_name";` lines, sorted by number
+    fn reserved_lines(&self, name: impl Fn(&str) -> String) -> String {
+        if self.reserved.is_empty() {
+            return String::new();
+        }
+        let mut reserved: Vec<(&String, &u32)> = self.reserved.iter().collect();
+        reserved.sort_by_key(|(_, number)| **number);
+        let numbers: Vec<String> = reserved
+            .iter()
+            .map(|(_, number)| number.to_string())
+            .collect();
+        let names: Vec<String> = reserved
+            .iter()
+            .map(|(reserved, _)| format!("\"{}\"", name(reserved)))
+            .collect();
+        format!(
+            "  reserved {};\n  reserved {};\n",
+            numbers.join(", "),
+            names.join(", ")
+        )
+    }
+}
+
+/// The number-allocation file for one `.proto`, keyed by message and enum name
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FieldNumbers {
+    #[serde(default)]
+    pub messages: BTreeMap<String, Allocation>,
+    #[serde(default)]
+    pub enums: BTreeMap<String, Allocation>,
+}
+
+impl FieldNumbers {
+    /// Read an allocation file written by `to_json`
+    pub fn from_json(text: &str) -> Result<Self, String> {
+        serde_json::from_str(text).map_err(|error| format!("Invalid field numbers: {error}"))
+    }
+
+    /// Write the allocation file, with sorted keys so diffs stay small
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("field numbers only hold strings and numbers")
+    }
+}
+
+/// Proto package of a dictionary, `Base` becomes `base`
+pub fn package(dictionary: &str) -> String {
+    snake_case(dictionary)
+}
+
+/// Generate the `.proto` file of a validated definition file, allocating numbers for new
+/// fields and enum values in `numbers`
+pub fn generate(file: &DataLangFile, numbers: &mut FieldNumbers) -> Result<String, ParseError> {
+    let mut out = String::from("// Generated by datalang from a DataLang definition file\n");
+    out.push_str("syntax = \"proto3\";\n");
+    if let Some(dictionary) = file.dictionary_name() {
+        writeln!(out, "\npackage {};", package(dictionary)).unwrap();
+    }
+
+    for item in &file.items {
+        match item {
+            DataLangItem::Term { name, .. } | DataLangItem::Struct { name, .. } => {
+                if file.is_simple_term(name) {
+                    continue;
+                }
+                let allocation = numbers.messages.entry(name.clone()).or_default();
+                out.push('\n');
+                write_message(&mut out, file, name, allocation)?;
+            }
+            DataLangItem::States { name, states, .. } => {
+                let allocation = numbers.enums.entry(name.clone()).or_default();
+                out.push('\n');
+                write_enum(&mut out, file, name, states, allocation);
+            }
+            DataLangItem::Dictionary { .. } | DataLangItem::Import { .. } => {}
+        }
+    }
+    Ok(out)
+}
+
+fn write_message(
+    out: &mut String,
+    file: &DataLangFile,
+    name: &str,
+    allocation: &mut Allocation,
+) -> Result<(), ParseError> {
+    let fields = file.expand_fields(name)?;
+
+    // Fields of older versions get their numbers first, so they end up reserved
+    let mut names: Vec<String> = Vec::new();
+    for version in 1..file.version().unwrap_or(1) {
+        for field in file.expand_fields_at(name, version)? {
+            if !names.contains(&field.field_name()) {
+                names.push(field.field_name());
+            }
+        }
+    }
+    for field in &fields {
+        if !names.contains(&field.field_name()) {
+            names.push(field.field_name());
+        }
+    }
+    allocation.allocate(&names, 1);
+    allocation.reserve_missing(
+        &fields
+            .iter()
+            .map(ResolvedField::field_name)
+            .collect::<Vec<_>>(),
+    );
+
+    write_comment(out, "//", file.doc(name));
+    writeln!(out, "message {name} {{").unwrap();
+    if file.deprecation(name).is_some() {
+        out.push_str("  option deprecated = true;\n");
+    }
+    out.push_str(&allocation.reserved_lines(str::to_string));
+    for field in &fields {
+        let field_name = field.field_name();
+        write_comment(out, "  //", field.doc.as_deref());
+        let options = if field.deprecated.is_some() {
+            " [deprecated = true]"
+        } else {
+            ""
+        };
+        writeln!(
+            out,
+            "  string {field_name} = {}{options};",
+            allocation.numbers[&field_name]
+        )
+        .unwrap();
+    }
+    out.push_str("}\n");
+    Ok(())
+}
+
+/// Values are prefixed with the enum name, `ORDER_STATUS_PENDING`, as proto3 enum values
+/// share their scope with the enum. Zero is `ORDER_STATUS_UNSPECIFIED`, so removing any
+/// state still leaves a valid default.
+fn write_enum(
+    out: &mut String,
+    file: &DataLangFile,
+    name: &str,
+    states: &[String],
+    allocation: &mut Allocation,
+) {
+    allocation.allocate(states, 1);
+    allocation.reserve_missing(states);
+    let prefix = snake_case(name).to_uppercase();
+    let value_name = |state: &str| format!("{prefix}_{}", snake_case(state).to_uppercase());
+
+    write_comment(out, "//", file.doc(name));
+    writeln!(out, "enum {name} {{").unwrap();
+    if file.deprecation(name).is_some() {
+        out.push_str("  option deprecated = true;\n");
+    }
+    out.push_str(&allocation.reserved_lines(value_name));
+    writeln!(out, "  {prefix}_UNSPECIFIED = 0;").unwrap();
+    let mut values: Vec<(&String, u32)> = states
+        .iter()
+        .map(|state| (state, allocation.numbers[state]))
+        .collect();
+    values.sort_by_key(|(_, number)| *number);
+    for (state, number) in values {
+        writeln!(out, "  {} = {number};", value_name(state)).unwrap();
+    }
+    out.push_str("}\n");
+}
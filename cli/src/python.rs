@@ -0,0 +1,316 @@
+// Python export
+// Each dictionary becomes a module named after it in snake case. Simple terms become
+// `NewType`s over `str`, like the `String` they generate in Rust, state machine terms become
+// `str` enums and composite terms and structs become dataclasses or pydantic models with one
+// attribute per resolved term. Terms from other dictionaries, named with their dictionary or
+// brought in unqualified by an `import`, are imported from their module. Fields `@since` or
+// `@removed` make optional without a `@default` may be `None`.
+// Simple terms and enums come before the classes, since `@default` values call them when a
+// class is created. The only declared constraints are the `String` type of every term and
+// the transitions of state machines: dataclasses check the types in `__post_init__`, pydantic
+// models validate them and reject unknown fields, and enums get `can_transition_to`.
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+use crate::types::{DataLangFile, DataLangItem, ParseError, ResolvedField, Transition, snake_case};
+use crate::{external_dictionary, quoted};
+
+/// Kind of class generated for composite terms and structs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Style {
+    /// `@dataclass(kw_only=True)` classes, with no dependencies
+    #[default]
+    Dataclass,
+    /// pydantic v2 `BaseModel` classes rejecting unknown fields
+    Pydantic,
+}
+
+const KEYWORDS: [&str; 35] = [
+    "False", "None", "True", "and", "as", "assert", "async", "await", "break", "class", "continue",
+    "def", "del", "elif", "else", "except", "finally", "for", "from", "global", "if", "import",
+    "in", "is", "lambda", "nonlocal", "not", "or", "pass", "raise", "return", "try", "while",
+    "with", "yield",
+];
+
+/// Module other modules import a dictionary's types from, `SocialMedia` becomes `social_media`
+pub fn module_name(dictionary: &str) -> String {
+    snake_case(dictionary)
+}
+
+/// Attribute name of a field, with a trailing `_` when the Rust field name is a keyword
+pub fn attribute_name(field: &ResolvedField) -> String {
+    let name = field.field_name();
+    if KEYWORDS.contains(&name.as_str()) {
+        format!("{name}_")
+    } else {
+        name
+    }
+}
+
+/// Generate the module of a validated definition file
+pub fn generate(file: &DataLangFile, style: Style) -> Result<String, ParseError> {
+    let mut imports: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    let mut uses_enum = false;
+    let mut uses_new_type = false;
+    let mut uses_models = false;
+    let mut definitions = String::new();
+    let mut classes = String::new();
+
+    for item in &file.items {
+        match item {
+            DataLangItem::Term { name, .. } | DataLangItem::Struct { name, .. } => {
+                if file.is_simple_term(name) {
+                    uses_new_type = true;
+                    definitions.push_str("\n\n");
+                    write_new_type(&mut definitions, file, name);
+                    continue;
+                }
+
+                uses_models = true;
+                let fields = file.expand_fields(name)?;
+                for field in &fields {
+                    if let Some(dictionary) = external_dictionary(file, field)? {
+                        let names = imports.entry(module_name(dictionary)).or_default();
+                        if !names.contains(&field.term) {
+                            names.push(field.term.clone());
+                        }
+                    }
+                }
+                classes.push_str("\n\n");
+                write_class(&mut classes, file, name, &fields, style);
+            }
+            DataLangItem::States {
+                name,
+                states,
+                transitions,
+                ..
+            } => {
+                uses_enum = true;
+                definitions.push_str("\n\n");
+                write_enum(&mut definitions, file, name, states, transitions);
+            }
+            DataLangItem::Dictionary { .. } | DataLangItem::Import { .. } => {}
+        }
+    }
+
+    let mut out = String::from("# Generated by datalang from a DataLang definition file\n");
+    if let Some(dictionary) = file.dictionary_name() {
+        writeln!(out, "\"\"\"Types of the {dictionary} dictionary\"\"\"").unwrap();
+    }
+    out.push_str("\nfrom __future__ import annotations\n\n");
+    if uses_models && style == Style::Dataclass {
+        out.push_str("from dataclasses import dataclass\n");
+    }
+    if uses_enum {
+        out.push_str("from enum import Enum\n");
+    }
+    if uses_new_type {
+        out.push_str("from typing import NewType\n");
+    }
+    if uses_models && style == Style::Pydantic {
+        out.push_str("\nfrom pydantic import BaseModel, ConfigDict, Field\n");
+    }
+    if !imports.is_empty() {
+        out.push('\n');
+    }
+    for (module, mut names) in imports {
+        names.sort();
+        writeln!(out, "from .{module} import {}", names.join(", ")).unwrap();
+    }
+    out.push_str(&definitions);
+    out.push_str(&classes);
+    Ok(out)
+}
+
+fn write_new_type(out: &mut String, file: &DataLangFile, name: &str) {
+    writeln!(out, "{name} = NewType({}, str)", quoted(name)).unwrap();
+    write_docstring(
+        out,
+        "",
+        &docs(file.doc(name), file.deprecation(name).map(|d| d.note)),
+    );
+}
+
+/// Values keep the state names, so `OrderStatus("Paid")` parses the Rust `Display` output
+fn write_enum(
+    out: &mut String,
+    file: &DataLangFile,
+    name: &str,
+    states: &[String],
+    transitions: &[Transition],
+) {
+    writeln!(out, "class {name}(str, Enum):").unwrap();
+    write_docstring(out, "    ", &docs(file.doc(name), None));
+    for state in states {
+        writeln!(
+            out,
+            "    {} = {}",
+            snake_case(state).to_uppercase(),
+            quoted(state)
+        )
+        .unwrap();
+    }
+
+    let pairs: Vec<String> = transitions
+        .iter()
+        .map(|(from, to)| format!("({}, {})", quoted(from), quoted(to)))
+        .collect();
+    writeln!(
+        out,
+        "\n    def can_transition_to(self, target: {name}) -> bool:"
+    )
+    .unwrap();
+    out.push_str("        \"\"\"Whether the state machine allows moving to `target`\"\"\"\n");
+    writeln!(
+        out,
+        "        return (self.value, target.value) in {{{}}}",
+        pairs.join(", ")
+    )
+    .unwrap();
+}
+
+/// Whether data may lack the field with nothing to fill it in, so the attribute may be `None`
+fn is_nullable(field: &ResolvedField) -> bool {
+    field.is_optional() && field.default.is_none()
+}
+
+/// Attributes are keyword-only, so those with a default can come in any order
+fn write_class(
+    out: &mut String,
+    file: &DataLangFile,
+    name: &str,
+    fields: &[ResolvedField],
+    style: Style,
+) {
+    match style {
+        Style::Dataclass => writeln!(out, "@dataclass(kw_only=True)\nclass {name}:").unwrap(),
+        Style::Pydantic => writeln!(out, "class {name}(BaseModel):").unwrap(),
+    }
+    let class_docs = docs(file.doc(name), file.deprecation(name).map(|d| d.note));
+    write_docstring(out, "    ", &class_docs);
+    if style == Style::Pydantic {
+        if !class_docs.is_empty() {
+            out.push('\n');
+        }
+        out.push_str("    model_config = ConfigDict(extra=\"forbid\", populate_by_name=True)\n");
+    }
+    if !class_docs.is_empty() || style == Style::Pydantic {
+        out.push('\n');
+    }
+
+    for field in fields {
+        let attribute = attribute_name(field);
+        let default = match &field.default {
+            Some(default) => Some(format!("{}({})", field.term, quoted(default))),
+            None if is_nullable(field) => Some("None".to_string()),
+            None => None,
+        };
+        let mut arguments = Vec::new();
+        if let Some(default) = &default {
+            arguments.push(format!("default={default}"));
+        }
+        if style == Style::Pydantic && attribute != field.field_name() {
+            arguments.push(format!("alias={}", quoted(&field.field_name())));
+        }
+        let doc = field
+            .doc
+            .as_deref()
+            .filter(|doc| file.doc(&field.term) != Some(*doc));
+        let deprecated = field.deprecated.as_ref().map(|d| d.note.clone());
+        if style == Style::Pydantic {
+            if let Some(doc) = doc {
+                arguments.push(format!("description={}", quoted(doc)));
+            }
+            if let Some(note) = &deprecated {
+                arguments.push(format!("deprecated={}", quoted(note)));
+            }
+        }
+
+        let nullable = if is_nullable(field) { " | None" } else { "" };
+        write!(out, "    {attribute}: {}{nullable}", field.term).unwrap();
+        match style {
+            Style::Dataclass => {
+                if let Some(default) = &default {
+                    write!(out, " = {default}").unwrap();
+                }
+            }
+            Style::Pydantic => {
+                if !arguments.is_empty() {
+                    write!(out, " = Field({})", arguments.join(", ")).unwrap();
+                }
+            }
+        }
+        out.push('\n');
+        if style == Style::Dataclass {
+            write_docstring(out, "    ", &docs(doc, deprecated));
+        }
+    }
+
+    // pydantic validates the types itself
+    if style == Style::Dataclass {
+        let (nullable, required): (Vec<&ResolvedField>, Vec<&ResolvedField>) =
+            fields.iter().partition(|field| is_nullable(field));
+        out.push_str("\n    def __post_init__(self) -> None:\n");
+        writeln!(out, "        for name in {}:", attribute_tuple(&required)).unwrap();
+        out.push_str("            if not isinstance(getattr(self, name), str):\n");
+        out.push_str("                raise TypeError(f\"{name} must be a str\")\n");
+        if !nullable.is_empty() {
+            writeln!(out, "        for name in {}:", attribute_tuple(&nullable)).unwrap();
+            out.push_str(
+                "            if not isinstance(getattr(self, name), (str, type(None))):\n",
+            );
+            out.push_str("                raise TypeError(f\"{name} must be a str or None\")\n");
+        }
+    }
+}
+
+/// A tuple literal of attribute names
+fn attribute_tuple(fields: &[&ResolvedField]) -> String {
+    let attributes: Vec<String> = fields
+        .iter()
+        .map(|field| quoted(&attribute_name(field)))
+        .collect();
+    match attributes.as_slice() {
+        [attribute] => format!("({attribute},)"),
+        attributes => format!("({})", attributes.join(", ")),
+    }
+}
+
+/// Lines of a docstring, with a deprecation note at the end
+fn docs(doc: Option<&str>, deprecated: Option<String>) -> Vec<String> {
+    let mut lines: Vec<String> = doc
+        .into_iter()
+        .flat_map(str::lines)
+        .map(str::to_string)
+        .collect();
+    if let Some(note) = deprecated {
+        if !lines.is_empty() {
+            lines.push(String::new());
+        }
+        lines.push(format!("Deprecated: {note}"));
+    }
+    lines
+}
+
+fn write_docstring(out: &mut String, indent: &str, lines: &[String]) {
+    let lines: Vec<String> = lines
+        .iter()
+        .map(|line| line.replace("\"\"\"", "\\\"\"\""))
+        .collect();
+    match lines.as_slice() {
+        [] => {}
+        [line] => writeln!(out, "{indent}\"\"\"{line}\"\"\"").unwrap(),
+        lines => {
+            writeln!(out, "{indent}\"\"\"{}", lines[0]).unwrap();
+            for line in &lines[1..] {
+                if line.is_empty() {
+                    out.push('\n');
+                } else {
+                    writeln!(out, "{indent}{line}").unwrap();
+                }
+            }
+            writeln!(out, "{indent}\"\"\"").unwrap();
+        }
+    }
+}
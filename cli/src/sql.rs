@@ -0,0 +1,161 @@
+// SQL DDL export
+// Composite terms and structs become tables with one TEXT column per resolved term, in
+// definition order. Columns are NOT NULL unless the field is optional, that is `@default`,
+// `@since` or `@removed` let data lack it. State machine terms cannot be fields, so they become
+// a table of their own in every dialect, with one column whose CHECK only accepts the states.
+// Identifiers are always quoted, since terms like `User` are reserved words in PostgreSQL.
+
+use std::fmt::{self, Write};
+use std::str::FromStr;
+
+use crate::types::{DataLangFile, DataLangItem, ParseError, ResolvedField, snake_case};
+use crate::write_comment;
+
+/// SQL dialect to generate statements for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Dialect {
+    #[default]
+    Sqlite,
+    Postgres,
+}
+
+impl FromStr for Dialect {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "sqlite" => Ok(Dialect::Sqlite),
+            "postgres" | "postgresql" => Ok(Dialect::Postgres),
+            _ => Err(format!(
+                "unknown SQL dialect '{s}', expected sqlite or postgres"
+            )),
+        }
+    }
+}
+
+impl fmt::Display for Dialect {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Dialect::Sqlite => "SQLite",
+            Dialect::Postgres => "PostgreSQL",
+        })
+    }
+}
+
+/// Table or type name of a term or struct, `SocialMediaUser` becomes `social_media_user`
+pub fn table_name(name: &str) -> String {
+    snake_case(name)
+}
+
+/// Generate the statements for a validated definition file.
+/// The output only depends on the file, so it can be checked in and diffed.
+pub fn generate(file: &DataLangFile, dialect: Dialect) -> Result<String, ParseError> {
+    let mut out =
+        format!("-- Generated by datalang from a DataLang definition file for {dialect}\n");
+    if let Some(dictionary) = file.dictionary_name() {
+        writeln!(out, "-- Dictionary: {dictionary}").unwrap();
+    }
+
+    for item in &file.items {
+        match item {
+            DataLangItem::Term { name, .. } | DataLangItem::Struct { name, .. } => {
+                if file.is_simple_term(name) {
+                    continue;
+                }
+                out.push('\n');
+                write_table(&mut out, file, name, &file.expand_fields(name)?, dialect);
+            }
+            DataLangItem::States { name, states, .. } => {
+                out.push('\n');
+                write_states_table(&mut out, file, name, states, dialect);
+            }
+            DataLangItem::Dictionary { .. } | DataLangItem::Import { .. } => {}
+        }
+    }
+    Ok(out)
+}
+
+fn write_table(
+    out: &mut String,
+    file: &DataLangFile,
+    name: &str,
+    fields: &[ResolvedField],
+    dialect: Dialect,
+) {
+    let table = identifier(&table_name(name));
+    if dialect == Dialect::Sqlite {
+        write_comment(out, "--", file.doc(name));
+    }
+    writeln!(out, "CREATE TABLE {table} (").unwrap();
+    let columns: Vec<String> = fields
+        .iter()
+        .map(|field| {
+            let mut column = String::new();
+            if dialect == Dialect::Sqlite {
+                write_comment(&mut column, "    --", field.doc.as_deref());
+            }
+            write!(column, "    {} TEXT", identifier(&field.field_name())).unwrap();
+            if !field.is_optional() {
+                column.push_str(" NOT NULL");
+            }
+            if let Some(default) = &field.default {
+                write!(column, " DEFAULT {}", literal(default)).unwrap();
+            }
+            column
+        })
+        .collect();
+    writeln!(out, "{}\n);", columns.join(",\n")).unwrap();
+
+    if dialect == Dialect::Postgres {
+        if let Some(doc) = file.doc(name) {
+            writeln!(out, "COMMENT ON TABLE {table} IS {};", literal(doc)).unwrap();
+        }
+        for field in fields {
+            if let Some(doc) = &field.doc {
+                let column = identifier(&field.field_name());
+                writeln!(
+                    out,
+                    "COMMENT ON COLUMN {table}.{column} IS {};",
+                    literal(doc)
+                )
+                .unwrap();
+            }
+        }
+    }
+}
+
+/// The table and its column are both named after the term, and the column only takes states
+fn write_states_table(
+    out: &mut String,
+    file: &DataLangFile,
+    name: &str,
+    states: &[String],
+    dialect: Dialect,
+) {
+    let table = identifier(&table_name(name));
+    let values: Vec<String> = states.iter().map(|state| literal(state)).collect();
+    if dialect == Dialect::Sqlite {
+        write_comment(out, "--", file.doc(name));
+    }
+    writeln!(
+        out,
+        "CREATE TABLE {table} (\n    {table} TEXT NOT NULL CHECK ({table} IN ({}))\n);",
+        values.join(", ")
+    )
+    .unwrap();
+    if dialect == Dialect::Postgres
+        && let Some(doc) = file.doc(name)
+    {
+        writeln!(out, "COMMENT ON TABLE {table} IS {};", literal(doc)).unwrap();
+    }
+}
+
+/// A double-quoted identifier
+fn identifier(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
+
+/// A single-quoted string literal
+fn literal(text: &str) -> String {
+    format!("'{}'", text.replace('\'', "''"))
+}
@@ -0,0 +1,153 @@
+// TypeScript export
+// Each dictionary becomes a module named after it. Simple terms become branded string
+// types, so a `Name` cannot be passed where a `LastName` is expected, state machine terms
+// become string unions and composite terms and structs become interfaces whose properties
+// use the Rust field names. Terms from other dictionaries, named with their dictionary or
+// brought in unqualified by an `import`, are imported from their module.
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+use crate::types::{DataLangFile, DataLangItem, ParseError, ResolvedField};
+use crate::{external_dictionary, quoted};
+
+/// Kind of file to generate
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Output {
+    /// A `.ts` module that also exports a constructor for every branded type
+    #[default]
+    Module,
+    /// A `.d.ts` file with type declarations only
+    Declarations,
+}
+
+/// Module path other modules import a dictionary's types from
+pub fn module_path(dictionary: &str) -> String {
+    format!("./{dictionary}")
+}
+
+/// Generate the module of a validated definition file
+pub fn generate(file: &DataLangFile, output: Output) -> Result<String, ParseError> {
+    let mut imports: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    let mut body = String::new();
+
+    for item in &file.items {
+        match item {
+            DataLangItem::Term { name, .. } | DataLangItem::Struct { name, .. } => {
+                body.push('\n');
+                if file.is_simple_term(name) {
+                    write_branded(&mut body, file, name, output);
+                    continue;
+                }
+
+                let fields = file.expand_fields(name)?;
+                for field in &fields {
+                    if let Some(dictionary) = external_dictionary(file, field)? {
+                        let names = imports.entry(dictionary.to_string()).or_default();
+                        if !names.contains(&field.term) {
+                            names.push(field.term.clone());
+                        }
+                    }
+                }
+                write_interface(&mut body, file, name, &fields);
+            }
+            DataLangItem::States { name, states, .. } => {
+                body.push('\n');
+                write_doc(&mut body, "", file.doc(name), None, None);
+                let states: Vec<String> = states.iter().map(|state| quoted(state)).collect();
+                writeln!(body, "export type {name} = {};", states.join(" | ")).unwrap();
+            }
+            DataLangItem::Dictionary { .. } | DataLangItem::Import { .. } => {}
+        }
+    }
+
+    let mut out = String::from("// Generated by datalang from a DataLang definition file\n");
+    if !imports.is_empty() {
+        out.push('\n');
+    }
+    for (dictionary, mut names) in imports {
+        names.sort();
+        writeln!(
+            out,
+            "import type {{ {} }} from {};",
+            names.join(", "),
+            quoted(&module_path(&dictionary))
+        )
+        .unwrap();
+    }
+    out.push_str(&body);
+    Ok(out)
+}
+
+/// Simple terms hold a `String`, so their branded type is based on `string`
+fn write_branded(out: &mut String, file: &DataLangFile, name: &str, output: Output) {
+    let deprecated = file.deprecation(name).map(|deprecation| deprecation.note);
+    write_doc(out, "", file.doc(name), deprecated.as_deref(), None);
+    writeln!(
+        out,
+        "export type {name} = string & {{ readonly __brand: {} }};",
+        quoted(name)
+    )
+    .unwrap();
+    if output == Output::Module {
+        writeln!(
+            out,
+            "export const {name} = (value: string): {name} => value as {name};"
+        )
+        .unwrap();
+    }
+}
+
+/// Optional fields, those with a `@default`, `@since` or `@removed`, may be missing from data,
+/// so their properties are optional
+fn write_interface(out: &mut String, file: &DataLangFile, name: &str, fields: &[ResolvedField]) {
+    let deprecated = file.deprecation(name).map(|deprecation| deprecation.note);
+    write_doc(out, "", file.doc(name), deprecated.as_deref(), None);
+    writeln!(out, "export interface {name} {{").unwrap();
+    for field in fields {
+        let doc = field
+            .doc
+            .as_deref()
+            .filter(|doc| file.doc(&field.term) != Some(*doc));
+        let deprecated = field
+            .deprecated
+            .as_ref()
+            .map(|deprecation| deprecation.note.as_str());
+        write_doc(out, "  ", doc, deprecated, field.default.as_deref());
+        let optional = if field.is_optional() { "?" } else { "" };
+        writeln!(out, "  {}{optional}: {};", field.field_name(), field.term).unwrap();
+    }
+    out.push_str("}\n");
+}
+
+/// A JSDoc comment, if there is anything to say
+fn write_doc(
+    out: &mut String,
+    indent: &str,
+    doc: Option<&str>,
+    deprecated: Option<&str>,
+    default: Option<&str>,
+) {
+    let mut lines: Vec<String> = doc
+        .into_iter()
+        .flat_map(str::lines)
+        .map(str::to_string)
+        .collect();
+    if let Some(default) = default {
+        lines.push(format!("@defaultValue {}", quoted(default)));
+    }
+    if let Some(note) = deprecated {
+        lines.push(format!("@deprecated {note}"));
+    }
+    match lines.as_slice() {
+        [] => {}
+        [line] => writeln!(out, "{indent}/** {} */", line.replace("*/", "*\\/")).unwrap(),
+        lines => {
+            writeln!(out, "{indent}/**").unwrap();
+            for line in lines {
+                writeln!(out, "{indent} * {}", line.replace("*/", "*\\/")).unwrap();
+            }
+            writeln!(out, "{indent} */").unwrap();
+        }
+    }
+}
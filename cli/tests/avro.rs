@@ -0,0 +1,99 @@
+use datalang_cli::avro;
+use serde_json::{Value, json};
+
+mod common;
+
+use common::{BASE, parse};
+
+#[test]
+fn test_records() {
+    let file = parse(BASE);
+    assert_eq!(
+        avro::schema(&file, "User").unwrap(),
+        json!({
+            "type": "record",
+            "name": "User",
+            "namespace": "base",
+            "fields": [
+                { "name": "name", "type": "string" },
+                { "name": "lastname", "type": "string" },
+            ],
+        })
+    );
+    assert!(avro::schema(&file, "Name").is_err());
+    assert!(avro::schema(&file, "Missing").is_err());
+}
+
+#[test]
+fn test_single_schema_is_not_a_union() {
+    let text = avro::to_string(&parse(BASE)).unwrap();
+    assert!(text.starts_with("{\n  \"type\": \"record\""));
+}
+
+#[test]
+fn test_output_is_identical_across_runs() {
+    let text = "dictionary Orders\nterm OrderStatus states { Pending -> Paid }\nterm OrderId {}\nterm Order has {\n    +OrderId\n}\n";
+    let first = avro::to_string(&parse(text)).unwrap();
+    assert_eq!(first, avro::to_string(&parse(text)).unwrap());
+    assert!(first.starts_with("[\n"));
+}
+
+#[test]
+fn test_enums() {
+    let file = parse("term OrderStatus states { Pending -> Paid; Pending -> Cancelled }\n");
+    assert_eq!(
+        avro::generate(&file).unwrap(),
+        [json!({
+            "type": "enum",
+            "name": "OrderStatus",
+            "symbols": ["Pending", "Paid", "Cancelled"],
+            "default": "Pending",
+        })]
+    );
+}
+
+#[test]
+fn test_docs_defaults_and_deprecation() {
+    let file = parse(
+        "dictionary Profiles version 2\n@doc(\"A login name\")\nterm Username {}\nterm Country {}\n@doc(\"A public profile\")\nProfile {\n    @deprecated(\"use the email\") +Username\n    @since(2) @default(\"US\") +Country\n}\n",
+    );
+    let schema = avro::schema(&file, "Profile").unwrap();
+    assert_eq!(schema["doc"], "A public profile");
+    assert_eq!(
+        schema["fields"],
+        json!([
+            { "name": "username", "type": "string", "doc": "A login name", "deprecated": "use the email" },
+            { "name": "country", "type": "string", "default": "US" },
+        ])
+    );
+}
+
+#[test]
+fn test_optional_fields_are_nullable_or_defaulted() {
+    let file = parse(
+        "dictionary Profiles version 2\nterm Username {}\nterm Email {}\nterm Country {}\nProfile {\n    +Username\n    @since(2) +Email\n    @default(\"US\") +Country\n}\n",
+    );
+    let fields = avro::schema(&file, "Profile").unwrap()["fields"].clone();
+    let types: Vec<(&str, &Value, Option<&Value>)> = fields
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|field| {
+            (
+                field["name"].as_str().unwrap(),
+                &field["type"],
+                field.get("default"),
+            )
+        })
+        .collect();
+
+    let nullable = json!(["null", "string"]);
+    assert_eq!(
+        types,
+        [
+            ("username", &json!("string"), None),
+            ("email", &nullable, Some(&Value::Null)),
+            ("country", &json!("string"), Some(&json!("US"))),
+        ]
+    );
+}
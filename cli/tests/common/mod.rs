@@ -0,0 +1,38 @@
+// Fixtures and helpers shared by the cli tests
+// Every test file is its own crate and uses a different part of them.
+#![allow(dead_code)]
+
+use datalang_cli::python::{self, Style};
+use datalang_cli::sql::{self, Dialect};
+use datalang_cli::typescript::{self, Output};
+use datalang_cli::{DataLangFile, graphql};
+
+/// Two simple terms and a `User` made of both
+pub const BASE: &str = "dictionary Base\nterm Name {}\nterm LastName {}\nterm User has {\n    +Name\n    +LastName\n}\n";
+
+/// Parse and validate a definition file
+pub fn parse(text: &str) -> DataLangFile {
+    let file = DataLangFile::parse_from_str(text).unwrap();
+    file.validate().unwrap();
+    file
+}
+
+/// GraphQL SDL of a definition file
+pub fn generate_graphql(text: &str) -> String {
+    graphql::generate(&parse(text)).unwrap()
+}
+
+/// Python module of a definition file
+pub fn generate_python(text: &str, style: Style) -> String {
+    python::generate(&parse(text), style).unwrap()
+}
+
+/// SQL statements of a definition file
+pub fn generate_sql(text: &str, dialect: Dialect) -> String {
+    sql::generate(&parse(text), dialect).unwrap()
+}
+
+/// TypeScript module of a definition file
+pub fn generate_typescript(text: &str, output: Output) -> String {
+    typescript::generate(&parse(text), output).unwrap()
+}
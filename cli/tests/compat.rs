@@ -0,0 +1,256 @@
+use datalang_cli::compat::{ChangeKind, Compatibility, check};
+
+mod common;
+
+use common::{BASE, parse};
+
+fn kinds(old: &str, new: &str) -> Vec<(ChangeKind, Compatibility)> {
+    check(&parse(old), &parse(new))
+        .unwrap()
+        .changes
+        .iter()
+        .map(|change| (change.kind, change.compatibility))
+        .collect()
+}
+
+#[test]
+fn test_identical_files_are_fully_compatible() {
+    let report = check(&parse(BASE), &parse(BASE)).unwrap();
+    assert!(report.changes.is_empty());
+    assert_eq!(report.compatibility, Compatibility::Full);
+}
+
+#[test]
+fn test_added_term_is_fully_compatible() {
+    let new = format!("{BASE}term Handle {{\n}}\n");
+    assert_eq!(
+        kinds(BASE, &new),
+        [(ChangeKind::TermAdded, Compatibility::Full)]
+    );
+}
+
+#[test]
+fn test_removed_field_is_backward_compatible() {
+    let new = BASE.replace("    +LastName\n", "");
+    let report = check(&parse(BASE), &parse(&new)).unwrap();
+
+    assert_eq!(report.compatibility, Compatibility::Backward);
+    assert_eq!(report.changes[0].kind, ChangeKind::FieldRemoved);
+    assert_eq!(report.changes[0].item, "User");
+    assert_eq!(report.changes[0].field.as_deref(), Some("LastName"));
+}
+
+#[test]
+fn test_added_field_is_forward_compatible() {
+    let new = BASE.replace("    +LastName\n", "    +LastName\n    +Base::Email\n");
+    assert_eq!(
+        kinds(BASE, &new),
+        [(ChangeKind::FieldAdded, Compatibility::Forward)]
+    );
+}
+
+#[test]
+fn test_added_optional_field_is_fully_compatible() {
+    let new = BASE.replace(
+        "    +LastName\n",
+        "    +LastName\n    @default(\"none\") +Base::Email\n",
+    );
+    assert_eq!(
+        kinds(BASE, &new),
+        [(ChangeKind::FieldAdded, Compatibility::Full)]
+    );
+
+    let old = BASE.replace("dictionary Base", "dictionary Base version 2");
+    let new = old.replace(
+        "    +LastName\n",
+        "    +LastName\n    @since(2) +Base::Email\n",
+    );
+    assert_eq!(
+        kinds(&old, &new),
+        [(ChangeKind::FieldAdded, Compatibility::Full)]
+    );
+}
+
+#[test]
+fn test_removed_optional_field_is_fully_compatible() {
+    let old = BASE.replace("    +LastName\n", "    @default(\"none\") +LastName\n");
+    let new = BASE.replace("    +LastName\n", "");
+    assert_eq!(
+        kinds(&old, &new),
+        [(ChangeKind::FieldRemoved, Compatibility::Full)]
+    );
+
+    // A required field stays breaking for consumers of the old version
+    assert_eq!(
+        kinds(BASE, &new),
+        [(ChangeKind::FieldRemoved, Compatibility::Backward)]
+    );
+}
+
+#[test]
+fn test_removed_term_is_breaking() {
+    let new = BASE
+        .replace("term LastName {}\n", "")
+        .replace("    +LastName\n", "");
+    let report = check(&parse(BASE), &parse(&new)).unwrap();
+
+    assert!(report.is_breaking());
+    assert!(
+        report
+            .changes
+            .iter()
+            .any(|change| change.kind == ChangeKind::TermRemoved)
+    );
+}
+
+#[test]
+fn test_mixed_directions_are_breaking() {
+    let new = BASE.replace("    +LastName\n", "    +Base::Email\n");
+    let report = check(&parse(BASE), &parse(&new)).unwrap();
+    assert_eq!(report.compatibility, Compatibility::Breaking);
+}
+
+#[test]
+fn test_origin_change_is_breaking() {
+    let new = BASE.replace("    +LastName\n", "    +Other::LastName\n");
+    assert_eq!(
+        kinds(BASE, &new),
+        [(ChangeKind::OriginChanged, Compatibility::Breaking)]
+    );
+}
+
+#[test]
+fn test_renamed_dictionary_is_breaking() {
+    let new = BASE.replace("dictionary Base", "dictionary People");
+    let changes = kinds(BASE, &new);
+
+    // Every field now comes from the renamed dictionary as well
+    assert_eq!(
+        changes[0],
+        (ChangeKind::DictionaryChanged, Compatibility::Breaking)
+    );
+    assert!(
+        changes[1..]
+            .iter()
+            .all(|(kind, _)| *kind == ChangeKind::OriginChanged)
+    );
+}
+
+#[test]
+fn test_added_and_removed_structs() {
+    let with_struct = format!("{BASE}Person {{\n    +Name\n}}\n");
+    assert_eq!(
+        kinds(BASE, &with_struct),
+        [(ChangeKind::StructAdded, Compatibility::Full)]
+    );
+    assert_eq!(
+        kinds(&with_struct, BASE),
+        [(ChangeKind::StructRemoved, Compatibility::Breaking)]
+    );
+}
+
+#[test]
+fn test_defaulted_field_is_optional() {
+    let new = BASE.replace("    +LastName\n", "    @default(\"none\") +LastName\n");
+    assert_eq!(
+        kinds(BASE, &new),
+        [(ChangeKind::CardinalityLoosened, Compatibility::Backward)]
+    );
+    assert_eq!(
+        kinds(&new, BASE),
+        [(ChangeKind::CardinalityTightened, Compatibility::Forward)]
+    );
+}
+
+#[test]
+fn test_versioned_fields_are_optional() {
+    let old = BASE.replace("dictionary Base", "dictionary Base version 2");
+    let new = old.replace("    +LastName\n", "    @since(2) +LastName\n");
+    assert_eq!(
+        kinds(&old, &new),
+        [(ChangeKind::CardinalityLoosened, Compatibility::Backward)]
+    );
+
+    // A field removed in the current version is no longer part of the item
+    let new = old.replace("    +LastName\n", "    @removed(2) +LastName\n");
+    assert_eq!(
+        kinds(&old, &new),
+        [(ChangeKind::FieldRemoved, Compatibility::Backward)]
+    );
+}
+
+#[test]
+fn test_reordered_fields_are_fully_compatible() {
+    let new = BASE.replace("    +Name\n    +LastName\n", "    +LastName\n    +Name\n");
+    assert_eq!(
+        kinds(BASE, &new),
+        [(ChangeKind::FieldsReordered, Compatibility::Full)]
+    );
+}
+
+const STATES: &str = "
+dictionary Orders
+term OrderStatus states {
+    Pending -> Paid -> Shipped
+    Pending -> Cancelled
+}
+";
+
+#[test]
+fn test_removed_state_is_breaking() {
+    let new = STATES.replace("    Pending -> Cancelled\n", "");
+    let report = check(&parse(STATES), &parse(&new)).unwrap();
+
+    assert!(report.is_breaking());
+    let changes: Vec<(ChangeKind, Option<&str>)> = report
+        .changes
+        .iter()
+        .map(|change| (change.kind, change.field.as_deref()))
+        .collect();
+    assert_eq!(
+        changes,
+        [
+            (ChangeKind::StateRemoved, Some("Cancelled")),
+            (ChangeKind::TransitionRemoved, Some("Pending -> Cancelled"))
+        ]
+    );
+}
+
+#[test]
+fn test_added_state_is_backward_compatible() {
+    let new = STATES.replace("Paid -> Shipped", "Paid -> Shipped -> Delivered");
+    assert_eq!(
+        kinds(STATES, &new),
+        [
+            (ChangeKind::StateAdded, Compatibility::Backward),
+            (ChangeKind::TransitionAdded, Compatibility::Backward)
+        ]
+    );
+}
+
+#[test]
+fn test_term_becoming_a_state_machine_is_breaking() {
+    let new = STATES.replace(
+        "term OrderStatus states {\n    Pending -> Paid -> Shipped\n    Pending -> Cancelled\n}",
+        "term OrderStatus {\n}",
+    );
+    assert_eq!(
+        kinds(&new, STATES),
+        [(ChangeKind::TermKindChanged, Compatibility::Breaking)]
+    );
+    assert_eq!(
+        kinds(STATES, &new),
+        [(ChangeKind::TermKindChanged, Compatibility::Breaking)]
+    );
+}
+
+#[test]
+fn test_json_report() {
+    let new = BASE.replace("    +LastName\n", "");
+    let report = check(&parse(BASE), &parse(&new)).unwrap();
+    let json: serde_json::Value = serde_json::from_str(&report.to_json()).unwrap();
+
+    assert_eq!(json["compatibility"], "backward");
+    assert_eq!(json["changes"][0]["kind"], "field_removed");
+    assert_eq!(json["changes"][0]["field"], "LastName");
+}
@@ -0,0 +1,63 @@
+use datalang_cli::DataLangFile;
+
+const DATES: &str = "
+dictionary Dates
+term DateOfBirth {
+}
+@deprecated(\"use DateOfBirth\", DateOfBirth)
+term BirthDate {
+}
+term Person has {
+    +BirthDate
+}
+Contact {
+    +Dates::BirthDate
+    +DateOfBirth
+}
+";
+
+#[test]
+fn test_warns_about_included_deprecated_terms() {
+    let file = DataLangFile::parse_from_str(DATES).unwrap();
+    file.validate().unwrap();
+
+    assert_eq!(
+        file.deprecation_warnings(),
+        [
+            "Person includes deprecated term BirthDate: use DateOfBirth",
+            "Contact includes deprecated term BirthDate: use DateOfBirth"
+        ]
+    );
+}
+
+#[test]
+fn test_deprecation_reaches_resolved_fields() {
+    let file = DataLangFile::parse_from_str(DATES).unwrap();
+    let fields = file.expand_fields("Person").unwrap();
+    let deprecated = fields[0].deprecated.as_ref().unwrap();
+
+    assert_eq!(deprecated.note, "use DateOfBirth");
+    assert_eq!(deprecated.replacement.as_deref(), Some("DateOfBirth"));
+}
+
+#[test]
+fn test_field_reference_deprecation() {
+    let file = DataLangFile::parse_from_str(
+        "term Nickname {\n}\nProfile {\n    @deprecated(\"hidden\") +Nickname\n}\n",
+    )
+    .unwrap();
+    file.validate().unwrap();
+
+    let fields = file.expand_fields("Profile").unwrap();
+    assert_eq!(fields[0].deprecated.as_ref().unwrap().note, "hidden");
+    // Only deprecated terms are reported, not deprecated references
+    assert!(file.deprecation_warnings().is_empty());
+}
+
+#[test]
+fn test_invalid_replacement() {
+    let file =
+        DataLangFile::parse_from_str("@deprecated(\"gone\", \"not a term\")\nterm Old {\n}\n")
+            .unwrap();
+    assert!(file.validate().is_err());
+}
@@ -0,0 +1,109 @@
+use datalang_cli::{DataLangFile, diagram};
+
+mod common;
+
+use common::parse;
+
+fn dictionaries() -> Vec<(String, DataLangFile)> {
+    vec![
+        (
+            "Base".to_string(),
+            parse(
+                "dictionary Base\nterm Name {}\nterm LastName {}\nterm User has {\n    +Name\n    +LastName\n}\n",
+            ),
+        ),
+        (
+            "SocialMedia".to_string(),
+            parse(
+                "dictionary SocialMedia\nimport Base\nterm Handle {}\nProfile {\n    +Base::User\n    -Base::LastName\n    +Handle\n    +Billing::Plan\n}\n",
+            ),
+        ),
+        (
+            "Orders".to_string(),
+            parse(
+                "dictionary Orders\nterm OrderStatus states { Pending -> Paid }\nterm OrderId {}\n",
+            ),
+        ),
+    ]
+}
+
+#[test]
+fn test_dot() {
+    let dot = diagram::generate(&dictionaries(), diagram::Format::Dot, None).unwrap();
+    assert!(dot.starts_with("digraph DataLang {\n    rankdir=LR;\n    compound=true;\n"));
+    assert!(dot.contains("    subgraph cluster_0 {\n        label=\"Base\";\n        \"Base::Name\" [label=\"Name\", shape=ellipse];\n"));
+    assert!(dot.contains("\"Base::User\" [label=\"User\", shape=box, style=rounded];"));
+    assert!(dot.contains("\"SocialMedia::Profile\" [label=\"Profile\", shape=box];"));
+    assert!(dot.contains("\"Orders::OrderStatus\" [label=\"OrderStatus\", shape=hexagon];"));
+    assert!(dot.contains("    \"Base::User\" -> \"Base::Name\" [label=\"has\"];\n"));
+    assert!(dot.contains("    \"SocialMedia::Profile\" -> \"Base::User\" [label=\"include\"];\n"));
+    assert!(dot.contains(
+        "    \"SocialMedia::Profile\" -> \"Base::LastName\" [label=\"exclude\", style=dashed];\n"
+    ));
+    assert!(dot.contains(
+        "    \"SocialMedia::Handle\" -> \"Base::Name\" [label=\"imports\", style=bold, ltail=cluster_1, lhead=cluster_0];\n"
+    ));
+    assert!(dot.ends_with("}\n"));
+}
+
+#[test]
+fn test_undefined_terms_are_dashed() {
+    let dot = diagram::generate(&dictionaries(), diagram::Format::Dot, None).unwrap();
+    assert!(dot.contains(
+        "    subgraph cluster_3 {\n        label=\"Billing\";\n        \"Billing::Plan\" [label=\"Plan\", shape=ellipse, style=dashed];\n    }\n"
+    ));
+}
+
+#[test]
+fn test_mermaid() {
+    let mermaid = diagram::generate(&dictionaries(), diagram::Format::Mermaid, None).unwrap();
+    assert!(
+        mermaid.starts_with("flowchart LR\n    subgraph d0 [\"Base\"]\n        d0n0(\"Name\")\n")
+    );
+    assert!(mermaid.contains("        d0n2([\"User\"])\n"));
+    assert!(mermaid.contains("        d1n1[\"Profile\"]\n"));
+    assert!(mermaid.contains("        d2n0{{\"OrderStatus\"}}\n"));
+    assert!(mermaid.contains("    d0n2 -->|has| d0n0\n"));
+    assert!(mermaid.contains("    d1n1 -.->|exclude| d0n1\n"));
+    assert!(mermaid.contains("    d1 ==>|imports| d0\n"));
+    assert!(mermaid.contains("        d3n0(\"Plan\"):::external\n"));
+    assert!(mermaid.ends_with("    classDef external stroke-dasharray: 5 5\n"));
+}
+
+#[test]
+fn test_focus_keeps_transitive_references() {
+    let dot = diagram::generate(&dictionaries(), diagram::Format::Dot, Some("Profile")).unwrap();
+    for node in [
+        "Base::Name",
+        "Base::LastName",
+        "Base::User",
+        "SocialMedia::Handle",
+        "Billing::Plan",
+    ] {
+        assert!(dot.contains(&format!("\"{node}\" [")), "{node} is missing");
+    }
+    assert!(dot.contains("\"Base::User\" -> \"Base::Name\" [label=\"has\"];"));
+    assert!(!dot.contains("Orders"));
+    assert!(dot.contains("label=\"imports\""));
+
+    let user =
+        diagram::generate(&dictionaries(), diagram::Format::Dot, Some("Base::User")).unwrap();
+    assert!(!user.contains("SocialMedia"));
+    assert!(!user.contains("imports"));
+    assert!(user.contains("\"Base::LastName\" [label=\"LastName\", shape=ellipse];"));
+}
+
+#[test]
+fn test_focus_errors() {
+    let mut dictionaries = dictionaries();
+    assert!(diagram::generate(&dictionaries, diagram::Format::Dot, Some("Missing")).is_err());
+    assert!(diagram::generate(&dictionaries, diagram::Format::Dot, Some("Orders::User")).is_err());
+
+    dictionaries.push((
+        "Other".to_string(),
+        parse("dictionary Other\nterm Name {}\n"),
+    ));
+    let error = diagram::generate(&dictionaries, diagram::Format::Dot, Some("Name")).unwrap_err();
+    assert!(error.to_string().contains("more than one dictionary"));
+    assert!(diagram::generate(&dictionaries, diagram::Format::Dot, Some("Other::Name")).is_ok());
+}
@@ -0,0 +1,128 @@
+use datalang_cli::docs;
+
+mod common;
+
+use common::parse;
+
+const BASE: &str = "dictionary Base\n@doc(\"Given name\")\nterm Name {}\nterm LastName {}\nterm User has {\n    +Name\n    @default(\"Doe\") +LastName\n}\n";
+const SOCIAL: &str = "dictionary SocialMedia\nimport Base\nterm Handle {}\nProfile {\n    +Base::User\n    +Base::Name\n    -Base::LastName\n    +Handle\n}\n";
+
+fn site(format: docs::Format) -> Vec<docs::Page> {
+    let dictionaries = vec![
+        ("Base".to_string(), parse(BASE)),
+        ("SocialMedia".to_string(), parse(SOCIAL)),
+    ];
+    docs::generate(&dictionaries, format).unwrap()
+}
+
+fn page<'a>(pages: &'a [docs::Page], path: &str) -> &'a str {
+    &pages
+        .iter()
+        .find(|page| page.path == path)
+        .unwrap_or_else(|| panic!("no page {path}"))
+        .content
+}
+
+#[test]
+fn test_page_per_dictionary_and_term() {
+    let pages = site(docs::Format::Markdown);
+    let paths: Vec<&str> = pages.iter().map(|page| page.path.as_str()).collect();
+    assert_eq!(
+        paths,
+        [
+            "index.md",
+            "Base/index.md",
+            "Base/Name.md",
+            "Base/LastName.md",
+            "Base/User.md",
+            "SocialMedia/index.md",
+            "SocialMedia/Handle.md",
+            "SocialMedia/Profile.md",
+        ]
+    );
+    assert!(
+        page(&pages, "index.md")
+            .contains("| [SocialMedia](SocialMedia/index.md) | 1 | 1 | [Base](Base/index.md) |")
+    );
+    let base = page(&pages, "Base/index.md");
+    assert!(base.contains("Imported by [SocialMedia](../SocialMedia/index.md)"));
+    assert!(base.contains("| [Name](Name.md) | Simple term | Given name |"));
+}
+
+#[test]
+fn test_field_table_links_across_imports() {
+    let pages = site(docs::Format::Markdown);
+    let profile = page(&pages, "SocialMedia/Profile.md");
+    assert!(profile.contains("- Includes [User](../Base/User.md) from [Base](../Base/index.md)"));
+    assert!(
+        profile
+            .contains("- Excludes [LastName](../Base/LastName.md) from [Base](../Base/index.md)")
+    );
+    assert!(profile.contains(
+        "| `name` | [Name](../Base/Name.md) | [Base](../Base/index.md) |  | Given name |"
+    ));
+    assert!(profile.contains("| `handle` | [Handle](Handle.md) | [SocialMedia](index.md) |  |  |"));
+    assert!(
+        profile.contains("| `user` | [User](../Base/User.md) | [Base](../Base/index.md) |  |  |")
+    );
+
+    let user = page(&pages, "Base/User.md");
+    assert!(
+        user.contains("| `lastname` | [LastName](LastName.md) | [Base](index.md) | `Doe` |  |")
+    );
+}
+
+#[test]
+fn test_usages() {
+    let pages = site(docs::Format::Markdown);
+    let last_name = page(&pages, "Base/LastName.md");
+    assert!(last_name.contains("- [User](User.md), included"));
+    assert!(last_name.contains(
+        "- [Profile](../SocialMedia/Profile.md) in [SocialMedia](../SocialMedia/index.md), excluded"
+    ));
+    assert!(last_name.contains("Field of\n\n- [User](User.md)\n"));
+    assert!(page(&pages, "SocialMedia/Profile.md").contains("Not used by any term or struct."));
+}
+
+#[test]
+fn test_state_machines() {
+    let dictionaries = vec![(
+        "Orders".to_string(),
+        parse(
+            "dictionary Orders\nterm OrderStatus states { Pending -> Paid; Pending -> Cancelled }\n",
+        ),
+    )];
+    let pages = docs::generate(&dictionaries, docs::Format::Markdown).unwrap();
+    let status = page(&pages, "Orders/OrderStatus.md");
+    assert!(status.contains("**State machine** in the [Orders](index.md) dictionary"));
+    assert!(status.contains("- `Pending` (initial)\n- `Paid`\n- `Cancelled`\n"));
+    assert!(status.contains("| `Pending` | `Cancelled` |"));
+}
+
+#[test]
+fn test_html() {
+    let pages = site(docs::Format::Html);
+    assert_eq!(pages[2].path, "Base/Name.html");
+    let profile = page(&pages, "SocialMedia/Profile.html");
+    assert!(profile.starts_with("<!DOCTYPE html>"));
+    assert!(profile.contains("<title>Profile</title>"));
+    assert!(profile.contains("<td><a href=\"../Base/Name.html\">Name</a></td>"));
+    assert!(profile.trim_end().ends_with("</html>"));
+}
+
+#[test]
+fn test_escaping() {
+    let dictionaries = vec![(
+        "Base".to_string(),
+        parse("dictionary Base\n@doc(\"a <b> & *c*\")\nterm Name {}\n"),
+    )];
+    let markdown = docs::generate(&dictionaries, docs::Format::Markdown).unwrap();
+    assert!(page(&markdown, "Base/Name.md").contains("a \\<b\\> & \\*c\\*"));
+    let html = docs::generate(&dictionaries, docs::Format::Html).unwrap();
+    assert!(page(&html, "Base/Name.html").contains("<p>a &lt;b&gt; &amp; *c*</p>"));
+}
+
+#[test]
+fn test_output_is_identical_across_runs() {
+    assert_eq!(site(docs::Format::Markdown), site(docs::Format::Markdown));
+}
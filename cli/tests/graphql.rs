@@ -0,0 +1,69 @@
+mod common;
+
+use common::generate_graphql;
+
+#[test]
+fn test_object_types() {
+    let sdl = generate_graphql(
+        "dictionary Base\nterm Name {}\nterm LastName {}\nterm User has {\n    +Name\n    +LastName\n}\n",
+    );
+    assert!(sdl.contains("# Dictionary: Base\n"));
+    assert!(sdl.contains("type User {\n  name: String!\n  lastname: String!\n}\n"));
+    assert!(!sdl.contains("type Name"));
+    assert!(!sdl.contains("scalar"));
+}
+
+#[test]
+fn test_terms_from_other_dictionaries() {
+    let sdl = generate_graphql(
+        "import Base\nterm Handle {}\nSocialMediaUser {\n    +Base::User\n    -Base::LastName\n    +Handle\n}\n",
+    );
+    assert!(sdl.contains("type SocialMediaUser {\n  user: String!\n  handle: String!\n}\n"));
+}
+
+#[test]
+fn test_state_machines_become_enums() {
+    let sdl =
+        generate_graphql("term OrderStatus states { Pending -> OutForDelivery -> Delivered }\n");
+    assert!(sdl.contains("enum OrderStatus {\n  PENDING\n  OUT_FOR_DELIVERY\n  DELIVERED\n}\n"));
+}
+
+#[test]
+fn test_descriptions_and_deprecation() {
+    let sdl = generate_graphql(
+        "@doc(\"Someone using the app\")\nterm User has {\n    @doc(\"Shown on the profile\") +Name\n    @deprecated(\"use \\\"Name\\\"\") +Nickname\n}\nterm Name {}\nterm Nickname {}\n",
+    );
+    assert!(sdl.contains("\"\"\"Someone using the app\"\"\"\ntype User {\n"));
+    assert!(sdl.contains("  \"\"\"Shown on the profile\"\"\"\n  name: String!\n"));
+    assert!(sdl.contains("  nickname: String! @deprecated(reason: \"use \\\"Name\\\"\")\n"));
+}
+
+#[test]
+fn test_composites_are_referenced_by_name() {
+    let sdl = generate_graphql(
+        "term Street {}\nterm City {}\nterm Name {}\nterm Address has {\n    +Street\n    +City\n}\nterm Customer has {\n    +Name\n    +Address\n}\n",
+    );
+    assert!(sdl.contains("type Address {\n  street: String!\n  city: String!\n}\n"));
+    assert!(sdl.contains("type Customer {\n  name: String!\n  address: Address!\n}\n"));
+}
+
+#[test]
+fn test_partly_excluded_composites_are_flattened() {
+    let sdl = generate_graphql(
+        "term Street {}\nterm City {}\nterm Address has {\n    +Street\n    +City\n}\nShipping {\n    +Address\n    -City\n}\n",
+    );
+    assert!(sdl.contains("type Shipping {\n  street: String!\n}\n"));
+}
+
+#[test]
+fn test_optional_fields_are_nullable() {
+    let sdl = generate_graphql(
+        "dictionary Profiles version 2\nterm Username {}\nterm Email {}\nterm Country {}\nterm Address has {\n    +Country\n}\nProfile {\n    +Username\n    @since(2) +Email\n    @default(\"US\") +Country\n}\nAccount {\n    +Username\n    @since(2) +Address\n}\n",
+    );
+    assert!(
+        sdl.contains(
+            "type Profile {\n  username: String!\n  email: String\n  country: String\n}\n"
+        )
+    );
+    assert!(sdl.contains("type Account {\n  username: String!\n  address: Address\n}\n"));
+}
@@ -0,0 +1,122 @@
+use datalang_cli::DataLangFile;
+use datalang_cli::json_schema::{self, DIALECT};
+use serde_json::json;
+
+mod common;
+
+use common::parse;
+
+#[test]
+fn test_terms_become_defs() {
+    let file = parse("dictionary Base\nterm Name {}\nterm User has {\n    +Name\n}\n");
+    let schema = json_schema::generate(&file).unwrap();
+
+    assert_eq!(schema["$schema"], DIALECT);
+    assert_eq!(schema["$id"], "Base.schema.json");
+    assert_eq!(schema["title"], "Base");
+    assert_eq!(schema["$defs"]["Name"], json!({ "type": "string" }));
+    assert_eq!(
+        schema["$defs"]["User"],
+        json!({
+            "type": "object",
+            "properties": { "name": { "$ref": "#/$defs/Name" } },
+            "required": ["name"],
+            "additionalProperties": false,
+        })
+    );
+}
+
+#[test]
+fn test_structs_reference_other_dictionaries() {
+    let file =
+        parse("import Base\nterm Handle {}\nSocialMediaUser {\n    +Base::Name\n    +Handle\n}\n");
+    let schema = json_schema::generate(&file).unwrap();
+
+    let properties = &schema["$defs"]["SocialMediaUser"]["properties"];
+    assert_eq!(properties["name"]["$ref"], "Base.schema.json#/$defs/Name");
+    assert_eq!(properties["handle"]["$ref"], "#/$defs/Handle");
+    assert!(schema.get("$id").is_none());
+}
+
+#[test]
+fn test_state_machines_become_enums() {
+    let file = parse("term Status states { Open -> Closed; Open -> Cancelled }\n");
+    let schema = json_schema::generate(&file).unwrap();
+
+    assert_eq!(
+        schema["$defs"]["Status"],
+        json!({ "type": "string", "enum": ["Open", "Closed", "Cancelled"] })
+    );
+}
+
+#[test]
+fn test_docs_defaults_and_deprecation() {
+    let file = parse(
+        "dictionary Profiles version 2\n\
+             @doc(\"A login name\")\n\
+             term Username {}\n\
+             @deprecated(\"use Username\")\n\
+             term Handle {}\n\
+             term Country {}\n\
+             @doc(\"A public profile\")\n\
+             Profile {\n\
+             \x20   +Username\n\
+             \x20   +Handle\n\
+             \x20   @since(2) @default(\"US\") @doc(\"Where the user lives\") +Country\n\
+             }\n",
+    );
+    let schema = json_schema::generate(&file).unwrap();
+    let defs = &schema["$defs"];
+
+    assert_eq!(defs["Username"]["description"], "A login name");
+    assert_eq!(defs["Handle"]["deprecated"], true);
+    assert_eq!(defs["Profile"]["description"], "A public profile");
+
+    let properties = &defs["Profile"]["properties"];
+    // The term's own doc is only on its definition
+    assert!(properties["username"].get("description").is_none());
+    assert_eq!(properties["handle"]["deprecated"], true);
+    assert_eq!(properties["country"]["default"], "US");
+    assert_eq!(properties["country"]["description"], "Where the user lives");
+    assert_eq!(defs["Profile"]["required"], json!(["username", "handle"]));
+}
+
+#[test]
+fn test_removed_fields_are_left_out() {
+    let file = parse(
+        "dictionary Profiles version 2\nterm Nickname {}\nterm Username {}\nProfile {\n    +Username\n    @removed(2) +Nickname\n}\n",
+    );
+    let schema = json_schema::generate(&file).unwrap();
+
+    let properties = schema["$defs"]["Profile"]["properties"]
+        .as_object()
+        .unwrap();
+    assert_eq!(properties.keys().collect::<Vec<_>>(), ["username"]);
+}
+
+#[test]
+fn test_versioned_fields_are_not_required() {
+    let file = parse(
+        "dictionary Profiles version 2\nterm Nickname {}\nterm Username {}\nProfile {\n    +Username\n    @since(2) +Nickname\n}\n",
+    );
+    let schema = json_schema::generate(&file).unwrap();
+    let profile = &schema["$defs"]["Profile"];
+
+    assert!(profile["properties"].get("nickname").is_some());
+    assert!(profile["properties"]["nickname"].get("default").is_none());
+    assert_eq!(profile["required"], json!(["username"]));
+}
+
+#[test]
+fn test_to_string_is_valid_json() {
+    let file = parse("dictionary Base\nterm Name {}\n");
+    let text = json_schema::to_string(&file).unwrap();
+    let value: serde_json::Value = serde_json::from_str(&text).unwrap();
+    assert_eq!(value, json_schema::generate(&file).unwrap());
+}
+
+#[test]
+fn test_doc_takes_one_string() {
+    let file = DataLangFile::parse_from_str("@doc(\"a\", \"b\")\nterm Name {}\n").unwrap();
+    assert!(file.validate().is_err());
+}
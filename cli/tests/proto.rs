@@ -0,0 +1,88 @@
+use datalang_cli::proto::{self, FieldNumbers};
+
+mod common;
+
+use common::{BASE, parse};
+
+#[test]
+fn test_messages_for_composite_terms() {
+    let mut numbers = FieldNumbers::default();
+    let text = proto::generate(&parse(BASE), &mut numbers).unwrap();
+
+    assert!(text.contains("syntax = \"proto3\";"));
+    assert!(text.contains("package base;"));
+    assert!(text.contains("message User {\n  string name = 1;\n  string lastname = 2;\n}\n"));
+    // Simple terms are scalars, not messages
+    assert!(!text.contains("message Name"));
+    assert_eq!(numbers.messages["User"].numbers["lastname"], 2);
+}
+
+#[test]
+fn test_removed_fields_keep_their_numbers() {
+    let mut numbers = FieldNumbers::default();
+    proto::generate(&parse(BASE), &mut numbers).unwrap();
+
+    let without_name = "dictionary Base\nterm Name {}\nterm LastName {}\nterm Nickname {}\nterm User has {\n    +LastName\n    +Nickname\n}\n";
+    let text = proto::generate(&parse(without_name), &mut numbers).unwrap();
+    assert!(text.contains("  reserved 1;\n  reserved \"name\";\n"));
+    assert!(text.contains("string lastname = 2;"));
+    assert!(text.contains("string nickname = 3;"));
+
+    // Adding the field back revives its old number
+    let text = proto::generate(&parse(BASE), &mut numbers).unwrap();
+    assert!(text.contains("string name = 1;"));
+    assert_eq!(numbers.messages["User"].reserved["nickname"], 3);
+}
+
+#[test]
+fn test_numbers_round_trip() {
+    let mut numbers = FieldNumbers::default();
+    let first = proto::generate(&parse(BASE), &mut numbers).unwrap();
+
+    let mut reloaded = FieldNumbers::from_json(&numbers.to_json()).unwrap();
+    assert_eq!(reloaded, numbers);
+    assert_eq!(proto::generate(&parse(BASE), &mut reloaded).unwrap(), first);
+}
+
+#[test]
+fn test_versioned_removals_are_reserved() {
+    let file = parse(
+        "dictionary Profiles version 2\nterm Username {}\nterm Nickname {}\nProfile {\n    +Username\n    @removed(2) +Nickname\n}\n",
+    );
+    let text = proto::generate(&file, &mut FieldNumbers::default()).unwrap();
+    assert!(text.contains("  reserved 2;\n  reserved \"nickname\";\n  string username = 1;\n"));
+}
+
+#[test]
+fn test_state_machines_become_enums() {
+    let mut numbers = FieldNumbers::default();
+    let text = proto::generate(
+        &parse("term OrderStatus states { Pending -> Paid; Pending -> Cancelled }\n"),
+        &mut numbers,
+    )
+    .unwrap();
+    assert!(text.contains(
+        "enum OrderStatus {\n  ORDER_STATUS_UNSPECIFIED = 0;\n  ORDER_STATUS_PENDING = 1;\n  ORDER_STATUS_PAID = 2;\n  ORDER_STATUS_CANCELLED = 3;\n}\n"
+    ));
+
+    let text = proto::generate(
+        &parse("term OrderStatus states { Pending -> Cancelled }\n"),
+        &mut numbers,
+    )
+    .unwrap();
+    assert!(text.contains("  reserved 2;\n  reserved \"ORDER_STATUS_PAID\";\n"));
+    assert!(text.contains("ORDER_STATUS_CANCELLED = 3;"));
+}
+
+#[test]
+fn test_docs_and_deprecation() {
+    let file = parse(
+        "@doc(\"Someone using the app\")\n@deprecated(\"use Account\")\nterm User has {\n    @doc(\"Shown on the profile\") @deprecated(\"unused\") +Name\n}\nterm Name {}\n",
+    );
+    let text = proto::generate(&file, &mut FieldNumbers::default()).unwrap();
+    assert!(
+        text.contains("// Someone using the app\nmessage User {\n  option deprecated = true;\n")
+    );
+    assert!(text.contains("  // Shown on the profile\n  string name = 1 [deprecated = true];\n"));
+    assert!(!text.contains("package"));
+}
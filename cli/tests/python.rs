@@ -0,0 +1,183 @@
+use std::process::Command;
+
+use datalang_cli::python::Style;
+
+mod common;
+
+use common::{BASE, generate_python};
+
+#[test]
+fn test_dataclasses() {
+    let module = generate_python(BASE, Style::Dataclass);
+    assert!(module.contains("\"\"\"Types of the Base dictionary\"\"\"\n"));
+    assert!(module.contains("from dataclasses import dataclass\nfrom typing import NewType\n"));
+    assert!(module.contains("Name = NewType(\"Name\", str)\n"));
+    assert!(module.contains(
+        "@dataclass(kw_only=True)\nclass User:\n    name: Name\n    lastname: LastName\n"
+    ));
+    assert!(!module.contains("pydantic"));
+}
+
+#[test]
+fn test_pydantic_models() {
+    let module = generate_python(BASE, Style::Pydantic);
+    assert!(module.contains("from pydantic import BaseModel, ConfigDict, Field\n"));
+    assert!(module.contains(
+        "class User(BaseModel):\n    model_config = ConfigDict(extra=\"forbid\", populate_by_name=True)\n\n    name: Name\n"
+    ));
+    assert!(!module.contains("dataclass"));
+}
+
+#[test]
+fn test_other_dictionaries_are_imported() {
+    let module = generate_python(
+        "import SocialMedia\nterm Bio {}\nProfile {\n    +SocialMedia::Handle\n    +Bio\n}\n",
+        Style::Dataclass,
+    );
+    assert!(module.contains("from .social_media import Handle\n"));
+}
+
+#[test]
+fn test_unqualified_imported_terms_are_imported() {
+    let module = generate_python(
+        "import SocialMedia\nterm Bio {}\nProfile {\n    +Handle\n    +Bio\n}\n",
+        Style::Dataclass,
+    );
+    assert!(module.contains("from .social_media import Handle\n"));
+    assert!(!module.contains("import Bio"));
+}
+
+#[test]
+fn test_versioned_fields_may_be_none() {
+    let text = "dictionary Profiles version 2\nterm Handle {}\nterm Nickname {}\nProfile {\n    +Handle\n    @since(2) +Nickname\n}\n";
+
+    let dataclass = generate_python(text, Style::Dataclass);
+    assert!(dataclass.contains("    nickname: Nickname | None = None\n"));
+    assert!(dataclass.contains(
+        "        for name in (\"nickname\",):\n            if not isinstance(getattr(self, name), (str, type(None))):\n"
+    ));
+
+    let pydantic = generate_python(text, Style::Pydantic);
+    assert!(pydantic.contains("    nickname: Nickname | None = Field(default=None)\n"));
+}
+
+#[test]
+fn test_state_machines_become_enums() {
+    let module = generate_python(
+        "@doc(\"Where an order is\")\nterm OrderStatus states { Pending -> OutForDelivery }\n",
+        Style::Dataclass,
+    );
+    assert!(module.contains("from enum import Enum\n"));
+    assert!(module.contains(
+        "class OrderStatus(str, Enum):\n    \"\"\"Where an order is\"\"\"\n    PENDING = \"Pending\"\n    OUT_FOR_DELIVERY = \"OutForDelivery\"\n"
+    ));
+}
+
+#[test]
+fn test_defaults_docs_and_deprecation() {
+    let text = "dictionary Profiles version 2\nterm Country {}\nterm Handle {}\n@doc(\"A public profile\")\nProfile {\n    @deprecated(\"use the user name\") +Handle\n    @since(2) @default(\"US\") @doc(\"Where they live\") +Country\n}\n";
+
+    let dataclass = generate_python(text, Style::Dataclass);
+    assert!(dataclass.contains("class Profile:\n    \"\"\"A public profile\"\"\"\n\n"));
+    assert!(
+        dataclass.contains("    handle: Handle\n    \"\"\"Deprecated: use the user name\"\"\"\n")
+    );
+    assert!(
+        dataclass
+            .contains("    country: Country = Country(\"US\")\n    \"\"\"Where they live\"\"\"\n")
+    );
+
+    let pydantic = generate_python(text, Style::Pydantic);
+    assert!(pydantic.contains("    handle: Handle = Field(deprecated=\"use the user name\")\n"));
+    assert!(pydantic.contains(
+        "    country: Country = Field(default=Country(\"US\"), description=\"Where they live\")\n"
+    ));
+}
+
+#[test]
+fn test_transitions_and_type_checks() {
+    let text =
+        "term Name {}\nterm Person has {\n    +Name\n}\nterm Status states { Open -> Closed }\n";
+    let dataclass = generate_python(text, Style::Dataclass);
+    assert!(dataclass.contains(
+        "    def can_transition_to(self, target: Status) -> bool:\n        \"\"\"Whether the state machine allows moving to `target`\"\"\"\n        return (self.value, target.value) in {(\"Open\", \"Closed\")}\n"
+    ));
+    assert!(dataclass.contains(
+        "    def __post_init__(self) -> None:\n        for name in (\"name\",):\n            if not isinstance(getattr(self, name), str):\n"
+    ));
+    assert!(!generate_python(text, Style::Pydantic).contains("__post_init__"));
+}
+
+/// Run `script` with the module generated from `text` importable as `profiles`.
+/// These tests need `python3`, and pydantic for `Style::Pydantic`.
+fn run_python(text: &str, style: Style, script: &str) -> String {
+    let dir =
+        std::env::temp_dir().join(format!("datalang-python-{}-{style:?}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("profiles.py"), generate_python(text, style)).unwrap();
+    let output = Command::new("python3")
+        .arg("-c")
+        .arg(script)
+        .env("PYTHONPATH", &dir)
+        .output();
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    let output = output.expect("python3 is required to run the generated modules");
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8_lossy(&output.stdout).into_owned()
+}
+
+// Defaults call the term's `NewType`, which is defined after the class in the file
+const PROFILES: &str = "dictionary Profiles version 2\nProfile {\n    +Handle\n    @default(\"US\") +Country\n    @since(2) +Nickname\n}\nterm OrderStatus states { Pending -> Paid }\nterm Handle {}\nterm Country {}\nterm Nickname {}\n";
+
+#[test]
+fn test_dataclass_module_imports() {
+    let script = "
+from profiles import OrderStatus, Profile
+profile = Profile(handle='@jane')
+assert profile.country == 'US'
+assert profile.nickname is None
+assert Profile(handle='@jane', nickname='jj').nickname == 'jj'
+try:
+    Profile(handle=1)
+    raise AssertionError('a non-str handle was accepted')
+except TypeError:
+    pass
+assert OrderStatus.PENDING.can_transition_to(OrderStatus.PAID)
+assert not OrderStatus.PAID.can_transition_to(OrderStatus.PENDING)
+print('ok')
+";
+    assert_eq!(run_python(PROFILES, Style::Dataclass, script), "ok\n");
+}
+
+#[test]
+fn test_pydantic_module_imports() {
+    let script = "
+import pydantic
+from profiles import Profile
+assert Profile(handle='@jane').country == 'US'
+assert Profile(handle='@jane').nickname is None
+for invalid in [{'handle': 1}, {'handle': '@jane', 'extra': 'x'}]:
+    try:
+        Profile(**invalid)
+        raise AssertionError(f'{invalid} was accepted')
+    except pydantic.ValidationError:
+        pass
+print('ok')
+";
+    assert_eq!(run_python(PROFILES, Style::Pydantic, script), "ok\n");
+}
+
+#[test]
+fn test_keywords_are_renamed() {
+    let text = "term Class {}\nterm Lesson has {\n    +Class\n}\n";
+    assert!(generate_python(text, Style::Dataclass).contains("    class_: Class\n"));
+    assert!(
+        generate_python(text, Style::Pydantic)
+            .contains("    class_: Class = Field(alias=\"class\")\n")
+    );
+}
@@ -0,0 +1,75 @@
+use datalang_cli::sql::Dialect;
+
+mod common;
+
+use common::{BASE, generate_sql};
+
+#[test]
+fn test_tables_for_composite_terms() {
+    let ddl = generate_sql(BASE, Dialect::Sqlite);
+    assert!(ddl.contains(
+        "CREATE TABLE \"user\" (\n    \"name\" TEXT NOT NULL,\n    \"lastname\" TEXT NOT NULL\n);\n"
+    ));
+    assert!(!ddl.contains("CREATE TABLE \"name\""));
+}
+
+#[test]
+fn test_output_is_deterministic() {
+    assert_eq!(
+        generate_sql(BASE, Dialect::Postgres),
+        generate_sql(BASE, Dialect::Postgres)
+    );
+}
+
+#[test]
+fn test_defaults_and_docs() {
+    let text = "dictionary Profiles version 2\n@doc(\"Someone's profile\")\nSocialMediaProfile {\n    @since(2) @default(\"US\") @doc(\"Where they live\") +Country\n}\nterm Country {}\n";
+
+    let sqlite = generate_sql(text, Dialect::Sqlite);
+    assert!(sqlite.contains("-- Someone's profile\nCREATE TABLE \"social_media_profile\" (\n"));
+    assert!(sqlite.contains("    -- Where they live\n    \"country\" TEXT DEFAULT 'US'\n"));
+
+    let postgres = generate_sql(text, Dialect::Postgres);
+    assert!(
+        postgres.contains("COMMENT ON TABLE \"social_media_profile\" IS 'Someone''s profile';")
+    );
+    assert!(
+        postgres.contains(
+            "COMMENT ON COLUMN \"social_media_profile\".\"country\" IS 'Where they live';"
+        )
+    );
+}
+
+#[test]
+fn test_state_machines_are_checked_in_every_dialect() {
+    let text = "@doc(\"Where an order is\")\nterm OrderStatus states { Pending -> Paid; Pending -> Cancelled }\n";
+    for dialect in [Dialect::Sqlite, Dialect::Postgres] {
+        let ddl = generate_sql(text, dialect);
+        assert!(ddl.contains(
+            "CREATE TABLE \"order_status\" (\n    \"order_status\" TEXT NOT NULL CHECK (\"order_status\" IN ('Pending', 'Paid', 'Cancelled'))\n);\n"
+        ));
+        assert!(!ddl.contains("INSERT"));
+    }
+    assert!(generate_sql(text, Dialect::Sqlite).contains("-- Where an order is\nCREATE TABLE"));
+    assert!(
+        generate_sql(text, Dialect::Postgres)
+            .contains("COMMENT ON TABLE \"order_status\" IS 'Where an order is';")
+    );
+}
+
+#[test]
+fn test_optional_fields_are_nullable() {
+    let text = "dictionary Profiles version 2\nterm Username {}\nterm Nickname {}\nterm Country {}\nProfile {\n    +Username\n    @since(2) +Nickname\n    @default(\"US\") +Country\n}\n";
+    for dialect in [Dialect::Sqlite, Dialect::Postgres] {
+        assert!(generate_sql(text, dialect).contains(
+            "CREATE TABLE \"profile\" (\n    \"username\" TEXT NOT NULL,\n    \"nickname\" TEXT,\n    \"country\" TEXT DEFAULT 'US'\n);\n"
+        ));
+    }
+}
+
+#[test]
+fn test_dialects() {
+    assert_eq!("postgresql".parse::<Dialect>(), Ok(Dialect::Postgres));
+    assert_eq!("SQLite".parse::<Dialect>(), Ok(Dialect::Sqlite));
+    assert!("mysql".parse::<Dialect>().is_err());
+}
@@ -0,0 +1,32 @@
+use datalang_cli::DataLangFile;
+
+#[test]
+fn test_parse_state_machine() {
+    let file = DataLangFile::parse_from_str(
+        "term OrderStatus states { Pending -> Paid -> Shipped; Pending -> Cancelled }\n",
+    )
+    .unwrap();
+    file.validate().unwrap();
+
+    let (states, transitions) = file.state_machine("OrderStatus").unwrap();
+    assert_eq!(states, ["Pending", "Paid", "Shipped", "Cancelled"]);
+    assert_eq!(transitions.len(), 3);
+    assert_eq!(
+        transitions[2],
+        ("Pending".to_string(), "Cancelled".to_string())
+    );
+}
+
+#[test]
+fn test_state_machines_are_not_fields() {
+    let file = DataLangFile::parse_from_str(
+        "term Status states {\n    Open -> Closed\n}\nTicket {\n    +Status\n}\n",
+    )
+    .unwrap();
+    assert!(file.validate().is_err());
+}
+
+#[test]
+fn test_invalid_state_name() {
+    assert!(DataLangFile::parse_from_str("term Status states { Open -> 1Closed }\n").is_err());
+}
@@ -0,0 +1,77 @@
+use datalang_cli::typescript::{self, Output};
+
+mod common;
+
+use common::{BASE, generate_typescript, parse};
+
+#[test]
+fn test_branded_terms_and_interfaces() {
+    let module = generate_typescript(BASE, Output::Module);
+    assert!(module.contains("export type Name = string & { readonly __brand: \"Name\" };\n"));
+    assert!(module.contains("export const Name = (value: string): Name => value as Name;\n"));
+    assert!(module.contains("export interface User {\n  name: Name;\n  lastname: LastName;\n}\n"));
+}
+
+#[test]
+fn test_declarations_have_no_values() {
+    let declarations = generate_typescript(BASE, Output::Declarations);
+    assert!(declarations.contains("export type Name = "));
+    assert!(!declarations.contains("export const"));
+}
+
+#[test]
+fn test_other_dictionaries_are_imported() {
+    let module = generate_typescript(
+        "import Base\nterm Handle {}\nSocialMediaUser {\n    +Base::Name\n    +Base::LastName\n    +Handle\n}\n",
+        Output::Module,
+    );
+    assert!(module.contains("import type { LastName, Name } from \"./Base\";\n"));
+    assert!(module.contains("  lastname: LastName;\n  handle: Handle;\n"));
+}
+
+#[test]
+fn test_unqualified_imported_terms_are_imported() {
+    let module = generate_typescript(
+        "import Base\nterm Handle {}\nSocialMediaUser {\n    +Name\n    +Handle\n}\n",
+        Output::Module,
+    );
+    assert!(module.contains("import type { Name } from \"./Base\";\n"));
+    assert!(module.contains("  name: Name;\n  handle: Handle;\n"));
+
+    // Without the other files the term could come from either import
+    let file = parse("import Base\nimport Social\nUser {\n    +Name\n}\n");
+    assert!(typescript::generate(&file, Output::Module).is_err());
+}
+
+#[test]
+fn test_optional_fields_are_optional_properties() {
+    let module = generate_typescript(
+        "dictionary Profiles version 2\nterm Username {}\nterm Nickname {}\nterm Country {}\nProfile {\n    +Username\n    @since(2) +Nickname\n    @default(\"US\") +Country\n}\n",
+        Output::Module,
+    );
+    assert!(module.contains(
+        "export interface Profile {\n  username: Username;\n  nickname?: Nickname;\n  /** @defaultValue \"US\" */\n  country?: Country;\n}\n"
+    ));
+}
+
+#[test]
+fn test_state_machines_become_unions() {
+    let module = generate_typescript(
+        "term OrderStatus states { Pending -> Paid; Pending -> Cancelled }\n",
+        Output::Module,
+    );
+    assert!(module.contains("export type OrderStatus = \"Pending\" | \"Paid\" | \"Cancelled\";\n"));
+}
+
+#[test]
+fn test_jsdoc() {
+    let module = generate_typescript(
+        "dictionary Profiles version 2\n@doc(\"A country code\")\nterm Country {}\n@deprecated(\"use Profile\")\nAccount {\n    @since(2) @default(\"US\") @doc(\"Where they live\") +Country\n}\n",
+        Output::Module,
+    );
+    assert!(module.contains("/** A country code */\nexport type Country"));
+    assert!(module.contains("/** @deprecated use Profile */\nexport interface Account {\n"));
+    assert!(module.contains(
+        "  /**\n   * Where they live\n   * @defaultValue \"US\"\n   */\n  country?: Country;\n"
+    ));
+}
@@ -1,10 +1,47 @@
+/// `Accounts` is `pub(crate)`, so other crates cannot build entities from its terms:
+///
+/// ```compile_fail
+/// use datalang_tests::*;
+///
+/// #[datalang::entity(+Accounts::Email)]
+/// pub struct Contact {}
+/// ```
+///
+/// Entities built from its public `Account` keep `PasswordHash` private:
+///
+/// ```compile_fail,E0616
+/// mod login {
+///     use datalang_tests::*;
+///
+///     #[datalang::entity(+Accounts::Account)]
+///     pub struct Login {}
+/// }
+///
+/// let mut login = login::Login::new();
+/// login.passwordhash = "hash".to_string();
+/// ```
+pub mod accounts {
+    datalang::datalang_file!("text_definitions/accounts.txt");
+}
+
 pub mod base {
-    include!("../macro_definitions/base.rs");
+    datalang::datalang_file!("text_definitions/base.txt");
+}
+
+pub mod orders {
+    datalang::datalang_file!("text_definitions/orders.txt");
+}
+
+pub mod profiles {
+    datalang::datalang_file!("text_definitions/profiles.txt");
 }
 
 pub mod social_media {
-    include!("../macro_definitions/social_media.rs");
+    datalang::datalang_file!("text_definitions/social_media.txt");
 }
 
+pub use accounts::*;
 pub use base::*;
+pub use orders::*;
+pub use profiles::*;
 pub use social_media::*;
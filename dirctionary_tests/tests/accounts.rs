@@ -0,0 +1,56 @@
+use datalang_runtime::text::ParseRecordError;
+use datalang_runtime::{PrivateField, Record, Value};
+use datalang_tests::*;
+
+fn main() {
+    // This allows running as an example too
+    println!("Run 'cargo test' to execute the actual tests");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_public_term_in_internal_dictionary() {
+        // Account is declared `pub`, so it is reachable even though Accounts is pub(crate)
+        let mut account = Account::new();
+        account.email = "jane@example.com".to_string();
+
+        assert_eq!(account.email, "jane@example.com");
+
+        // Email and PasswordHash inherit pub(crate) from the dictionary
+        // let _ = Email::new(); // This should NOT compile
+    }
+
+    #[test]
+    fn test_private_field_has_getter() {
+        let account = Account::new();
+        assert_eq!(account.passwordhash(), "");
+        assert_eq!(account.get(AccountField::PasswordHash).as_str(), "");
+
+        // account.passwordhash = "hash".to_string(); // This should NOT compile
+    }
+
+    #[test]
+    fn test_generic_access_cannot_write_private_fields() {
+        let mut account = Account::new();
+        assert_eq!(
+            account.set(AccountField::PasswordHash, Value::from("hash")),
+            Err(PrivateField("PasswordHash"))
+        );
+        assert_eq!(account.passwordhash(), "");
+
+        // The text form leaves private fields out and refuses to parse them
+        account.email = "jane@example.com".to_string();
+        assert_eq!(account.to_string(), "email=jane@example.com");
+        let parsed: Account = account.to_string().parse().unwrap();
+        assert_eq!(parsed.email, "jane@example.com");
+        assert_eq!(
+            "email=jane@example.com password_hash=hash"
+                .parse::<Account>()
+                .unwrap_err(),
+            ParseRecordError::PrivateField("password_hash")
+        );
+    }
+}
@@ -0,0 +1,93 @@
+use datalang_runtime::ArrowRecord;
+use datalang_runtime::arrow::arrow_array::{Array, ArrayRef, RecordBatch, StringArray};
+use datalang_runtime::arrow::arrow_schema::DataType;
+use datalang_runtime::arrow::{DICTIONARY_KEY, STRUCT_KEY, TERM_KEY};
+use datalang_tests::*;
+use std::sync::Arc;
+
+fn main() {
+    // This allows running as an example too
+    println!("Run 'cargo test' to execute the actual tests");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn users() -> Vec<User> {
+        vec![
+            User::with("John", "Doe", "1990-01-01"),
+            User::with("Jane", "Roe", "1995-05-15"),
+        ]
+    }
+
+    #[test]
+    fn test_schema_from_fields() {
+        let schema = User::arrow_schema().unwrap();
+        let names: Vec<&str> = schema.fields().iter().map(|f| f.name().as_str()).collect();
+        assert_eq!(names, ["name", "lastname", "birthdate"]);
+
+        let lastname = schema.field_with_name("lastname").unwrap();
+        assert_eq!(lastname.data_type(), &DataType::Utf8);
+        assert!(!lastname.is_nullable());
+        assert_eq!(lastname.metadata()[TERM_KEY], "LastName");
+        assert_eq!(lastname.metadata()[DICTIONARY_KEY], "Base");
+        assert_eq!(schema.metadata()[STRUCT_KEY], "User");
+    }
+
+    #[test]
+    fn test_record_batch_round_trip() {
+        let batch = User::to_record_batch(&users()).unwrap();
+        assert_eq!(batch.num_rows(), 2);
+
+        let lastnames = batch
+            .column_by_name("lastname")
+            .unwrap()
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .unwrap();
+        assert_eq!(lastnames.value(1), "Roe");
+
+        assert_eq!(User::from_record_batch(&batch).unwrap(), users());
+    }
+
+    #[test]
+    fn test_private_fields_get_no_column() {
+        let schema = Account::arrow_schema().unwrap();
+        assert!(schema.field_with_name("passwordhash").is_err());
+
+        let batch = Account::to_record_batch(&[Account::with("a@example.com")]).unwrap();
+        assert_eq!(batch.num_columns(), 1);
+        let accounts = Account::from_record_batch(&batch).unwrap();
+        assert_eq!(accounts[0].email, "a@example.com");
+    }
+
+    #[test]
+    fn test_fields_from_other_dictionaries() {
+        let schema = SocialMediaUser::arrow_schema().unwrap();
+        let handle = schema.field_with_name("handle").unwrap();
+        assert_eq!(handle.metadata()[TERM_KEY], "Handle");
+        assert!(handle.metadata().get(DICTIONARY_KEY).is_none());
+        assert_eq!(
+            schema.field_with_name("name").unwrap().metadata()[DICTIONARY_KEY],
+            "Base"
+        );
+    }
+
+    #[test]
+    fn test_missing_and_null_columns() {
+        let names: ArrayRef = Arc::new(StringArray::from(vec!["John"]));
+        let batch = RecordBatch::try_from_iter([("name", names)]).unwrap();
+        assert!(User::from_record_batch(&batch).is_err());
+
+        let with_null: ArrayRef = Arc::new(StringArray::from(vec![Some("John"), None]));
+        let other: ArrayRef = Arc::new(StringArray::from(vec!["a", "b"]));
+        let batch = RecordBatch::try_from_iter([
+            ("name", with_null),
+            ("lastname", other.clone()),
+            ("birthdate", other),
+        ])
+        .unwrap();
+        assert!(User::from_record_batch(&batch).is_err());
+    }
+}
@@ -1,5 +1,10 @@
 use datalang_tests::*;
 
+fn main() {
+    // This allows running as an example too
+    println!("Run 'cargo test' to execute the actual tests");
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -39,8 +44,3 @@ mod tests {
         assert_eq!(user.birthdate, "");
     }
 }
-
-fn main() {
-    // This allows running as an example too
-    println!("Run 'cargo test' to execute the actual tests");
-}
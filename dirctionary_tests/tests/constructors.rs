@@ -0,0 +1,54 @@
+use datalang_tests::*;
+
+fn main() {
+    // This allows running as an example too
+    println!("Run 'cargo test' to execute the actual tests");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_matches_new() {
+        assert_eq!(User::default(), User::new());
+        assert_eq!(SocialMediaUser::default().handle, "");
+        assert_eq!(Name::default().name, "");
+    }
+
+    #[test]
+    fn test_with_takes_fields_in_definition_order() {
+        let user = User::with("John", "Doe", "1990-01-01");
+
+        assert_eq!(user.name, "John");
+        assert_eq!(user.lastname, "Doe");
+        assert_eq!(user.birthdate, "1990-01-01");
+
+        let social_user = SocialMediaUser::with("Jane", "1995-05-15", String::from("@jane"));
+        assert_eq!(social_user.handle, "@jane");
+    }
+
+    #[test]
+    fn test_with_arguments() {
+        // Fails to compile if a field is added to or dropped from the arguments
+        let user: fn(String, String, String) -> User = User::with;
+        let account: fn(String) -> Account = Account::with;
+
+        let name = || "x".to_string();
+        assert_eq!(user(name(), "Doe".to_string(), name()).lastname, "Doe");
+        assert_eq!(account("a@example.com".to_string()).email, "a@example.com");
+    }
+
+    #[test]
+    fn test_with_leaves_out_private_fields() {
+        let account = Account::with("jane@example.com");
+
+        assert_eq!(account.email, "jane@example.com");
+        assert_eq!(account.passwordhash(), "");
+    }
+
+    #[test]
+    fn test_simple_term_with() {
+        assert_eq!(Handle::with("@jane").handle, "@jane");
+    }
+}
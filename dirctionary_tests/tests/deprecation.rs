@@ -0,0 +1,59 @@
+use datalang_tests::*;
+
+fn main() {
+    // This allows running as an example too
+    println!("Run 'cargo test' to execute the actual tests");
+}
+
+mod dates {
+    datalang::datalang! {
+        dictionary Dates
+
+        term DateOfBirth {
+        }
+
+        @deprecated("use DateOfBirth", DateOfBirth)
+        term BirthDate {
+        }
+
+        term Nickname {
+        }
+
+        term Person has {
+            +BirthDate
+            @deprecated("nicknames are no longer shown") +Nickname
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_forwarding_accessor() {
+        let person = dates::Person::with("1990-01-01", "JD");
+        assert_eq!(person.dateofbirth(), "1990-01-01");
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn test_deprecated_items_still_work() {
+        let mut person = dates::Person::new();
+        person.nickname = "JD".to_string();
+        assert_eq!(person.nickname, "JD");
+        assert_eq!(dates::BirthDate::with("1990-01-01").birthdate, "1990-01-01");
+    }
+
+    #[test]
+    fn test_replacement_is_not_deprecated() {
+        let date = dates::DateOfBirth::with("1990-01-01");
+        assert_eq!(date.dateofbirth, "1990-01-01");
+    }
+
+    #[test]
+    fn test_base_is_unaffected() {
+        let user = User::with("John", "Doe", "1990-01-01");
+        assert_eq!(user.birthdate, "1990-01-01");
+    }
+}
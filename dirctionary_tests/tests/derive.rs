@@ -0,0 +1,74 @@
+use datalang::DataLang;
+use datalang_runtime::{Record, Term, Value};
+use datalang_tests::*;
+
+fn main() {
+    // This allows running as an example too
+    println!("Run 'cargo test' to execute the actual tests");
+}
+
+/// Handwritten struct mapping some of its fields to dictionary terms
+#[derive(DataLang, Debug, Default)]
+#[datalang(dictionary = "Base")]
+struct Customer {
+    #[datalang(term = "Name")]
+    first_name: String,
+    #[datalang(term = "LastName")]
+    surname: String,
+    #[datalang(term = "Handle")]
+    handle: String,
+    // Not a term, so it is left out of the record
+    visits: u32,
+    // #[datalang(term = "Name")]
+    // wrong_type: u32, // This should NOT compile: Name holds a String
+    // #[datalang(term = "Handle", dictionary = "SocialMedia")]
+    // other: String, // This should NOT compile: the dictionary comes from the term
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_simple_terms_implement_term() {
+        assert_eq!(Name::NAME, "Name");
+        assert_eq!(Name::DICTIONARY, Some("Base"));
+        assert_eq!(Handle::DICTIONARY, None);
+    }
+
+    #[test]
+    fn test_derived_field_metadata() {
+        let fields: Vec<(&str, Option<&str>, &str, &str)> = Customer::FIELDS
+            .iter()
+            .map(|f| (f.term, f.dictionary, f.name, f.ty))
+            .collect();
+
+        assert_eq!(
+            fields,
+            [
+                ("Name", Some("Base"), "first_name", "String"),
+                ("LastName", Some("Base"), "surname", "String"),
+                ("Handle", None, "handle", "String"),
+            ]
+        );
+        assert_eq!(CustomerField::ALL.len(), 3);
+
+        // Field metadata follows the term, not the struct's dictionary
+        assert_eq!(CustomerField::Handle.info().dictionary, Handle::DICTIONARY);
+        assert_eq!(CustomerField::Name.info().ty, Name::TYPE);
+        assert_eq!(CustomerField::LastName.info().name, "surname");
+    }
+
+    #[test]
+    fn test_derived_get_and_set() {
+        let mut customer = Customer {
+            visits: 3,
+            ..Customer::default()
+        };
+        customer.set(CustomerField::Name, Value::from("Ada")).unwrap();
+
+        assert_eq!(customer.first_name, "Ada");
+        assert_eq!(customer.get(CustomerField::Name).as_str(), "Ada");
+        assert_eq!(customer.visits, 3);
+    }
+}
@@ -0,0 +1,143 @@
+use datalang_runtime::{DynRecord, DynRecordError, Record, Value, schema};
+use datalang_tests::*;
+
+fn main() {
+    // This allows running as an example too
+    println!("Run 'cargo test' to execute the actual tests");
+}
+
+/// A second `User`, so the bare name is ambiguous in this binary
+mod other {
+    datalang::datalang! {
+        dictionary Other
+
+        term Nickname {
+        }
+
+        term User has {
+            +Nickname
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_route_by_struct_name() {
+        let mut record = DynRecord::for_struct("Base::User").unwrap();
+        record.set("Name", "John").unwrap();
+        record.set("LastName", "Doe").unwrap();
+        record.set("BirthDate", "1990-01-01").unwrap();
+
+        assert_eq!(record.info().dictionary, Some("Base"));
+        assert_eq!(record.get("LastName"), Some(&Value::from("Doe")));
+
+        let user = User::try_from(record).unwrap();
+        assert_eq!(user, User::with("John", "Doe", "1990-01-01"));
+    }
+
+    #[test]
+    fn test_route_by_qualified_name() {
+        assert_eq!(
+            DynRecord::for_struct("User"),
+            Err(DynRecordError::AmbiguousStruct("User".to_string()))
+        );
+        assert_eq!(schema().find_struct("User"), None);
+
+        let base = DynRecord::for_struct("Base::User").unwrap();
+        assert_eq!(base.info().fields, User::FIELDS);
+
+        let mut other = DynRecord::for_struct("Other::User").unwrap();
+        other.set("Nickname", "jd").unwrap();
+        let user = other::User::try_from(other).unwrap();
+        assert_eq!(user.nickname, "jd");
+    }
+
+    #[test]
+    fn test_struct_to_dyn_record() {
+        let record = DynRecord::from(SocialMediaUser::with("Jane", "1995-05-15", "@jane"));
+        let pairs: Vec<(&str, &str)> = record
+            .iter()
+            .map(|(term, value)| (term, value.as_str()))
+            .collect();
+
+        assert_eq!(
+            pairs,
+            [
+                ("Name", "Jane"),
+                ("BirthDate", "1995-05-15"),
+                ("Handle", "@jane")
+            ]
+        );
+        assert!(record.validate().is_ok());
+    }
+
+    #[test]
+    fn test_unknown_struct_and_term() {
+        assert_eq!(
+            DynRecord::for_struct("Missing"),
+            Err(DynRecordError::UnknownStruct("Missing".to_string()))
+        );
+
+        let mut record = DynRecord::new(User::INFO);
+        assert_eq!(
+            record.set("Handle", "@john"),
+            Err(DynRecordError::UnknownTerm("Handle".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_missing_terms_are_reported() {
+        let mut record = DynRecord::new(User::INFO);
+        record.set("Name", "John").unwrap();
+
+        assert_eq!(
+            record.validate(),
+            Err(DynRecordError::MissingTerm("LastName"))
+        );
+        assert_eq!(
+            User::try_from(record).unwrap_err(),
+            DynRecordError::MissingTerm("LastName")
+        );
+    }
+
+    #[test]
+    fn test_conversion_checks_struct() {
+        let mut record = DynRecord::new(Name::INFO);
+        record.set("Name", "John").unwrap();
+
+        assert_eq!(
+            User::try_from(record.clone()).unwrap_err(),
+            DynRecordError::StructMismatch {
+                expected: "User",
+                found: "Name"
+            }
+        );
+        assert_eq!(Name::try_from(record).unwrap().name, "John");
+    }
+
+    #[test]
+    fn test_remove_clears_a_term() {
+        let mut record = DynRecord::from(User::with("John", "Doe", "1990-01-01"));
+        assert_eq!(record.remove("LastName"), Some(Value::from("Doe")));
+        assert_eq!(record.get("LastName"), None);
+        assert_eq!(record.get("Name").map(Value::as_str), Some("John"));
+    }
+
+    #[test]
+    fn test_private_terms_are_left_out() {
+        let mut record = DynRecord::from(Account::with("a@example.com"));
+        assert_eq!(record.get("PasswordHash"), None);
+        assert_eq!(
+            record.set("PasswordHash", "hash"),
+            Err(DynRecordError::PrivateTerm("PasswordHash".to_string()))
+        );
+        assert!(record.validate().is_ok());
+
+        let account = Account::try_from(record).unwrap();
+        assert_eq!(account.email, "a@example.com");
+        assert_eq!(account.passwordhash(), "");
+    }
+}
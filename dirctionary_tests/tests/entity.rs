@@ -0,0 +1,123 @@
+use datalang_runtime::Record;
+use datalang_tests::*;
+
+fn main() {
+    // This allows running as an example too
+    println!("Run 'cargo test' to execute the actual tests");
+}
+
+/// User terms without the last name, plus Rust-only bookkeeping
+#[datalang::entity(+Base::User, -Base::LastName, +Handle)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct Account {
+    retries: u32,
+    cache: Vec<String>,
+}
+
+/// Private references only get getters, like in `datalang!`
+#[datalang::entity(private +Base::Name)]
+pub struct Session {
+    token: u64,
+}
+
+/// Fields declared `@default(...)` start at their default
+#[datalang::entity(+Profiles::Profile)]
+pub struct Member {
+    visits: u32,
+}
+
+mod login {
+    use datalang_tests::*;
+
+    /// Fields a dictionary declares `private` stay private outside this module too
+    #[datalang::entity(+Accounts::Account)]
+    pub struct Login {
+        pub attempts: u32,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_terms_are_injected_before_handwritten_fields() {
+        let account = Account {
+            name: "Jane".to_string(),
+            birthdate: "1995-05-15".to_string(),
+            handle: "@jane".to_string(),
+            retries: 2,
+            cache: Vec::new(),
+        };
+
+        // let _ = account.lastname; // This should NOT compile
+        assert_eq!(account.retries, 2);
+    }
+
+    #[test]
+    fn test_constructors_cover_terms_and_handwritten_fields() {
+        let empty = Account::new(1, vec!["a".to_string()]);
+        assert_eq!(empty.name, "");
+        assert_eq!(empty.retries, 1);
+
+        let full = Account::with("Jane", "1995-05-15", "@jane", 0, Vec::new());
+        assert_eq!(full.birthdate, "1995-05-15");
+        assert_eq!(full.handle, "@jane");
+        assert!(full.cache.is_empty());
+    }
+
+    #[test]
+    fn test_record_covers_only_term_fields() {
+        let fields: Vec<(&str, Option<&str>)> = Account::FIELDS
+            .iter()
+            .map(|f| (f.name, f.dictionary))
+            .collect();
+
+        assert_eq!(
+            fields,
+            [
+                ("name", Some("Base")),
+                ("birthdate", Some("Base")),
+                ("handle", None)
+            ]
+        );
+        assert_eq!(AccountField::ALL.len(), 3);
+    }
+
+    #[test]
+    fn test_private_term_fields_get_getters() {
+        // Private fields are left out of `with`, like in `datalang!`
+        let session = Session::with(7);
+
+        assert_eq!(session.name(), "");
+        assert_eq!(session.token, 7);
+    }
+
+    #[test]
+    fn test_private_fields_from_the_dictionary_stay_private() {
+        let mut login = login::Login::with("jane@example.com", 3);
+        assert_eq!(login.email, "jane@example.com");
+        assert_eq!(login.passwordhash(), "");
+        assert_eq!(login.attempts, 3);
+
+        // login.passwordhash = "hash".to_string(); // This should NOT compile
+        assert!(
+            login
+                .set(login::LoginField::PasswordHash, "hash".into())
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_defaults_fill_defaulted_fields() {
+        let member = Member::new(0);
+        assert_eq!(member.country, "US");
+        assert_eq!(member.contactemail, "unknown@example.com");
+
+        // Only fields without a default are taken
+        let member = Member::with("jane", 2);
+        assert_eq!(member.username, "jane");
+        assert_eq!(member.country, "US");
+        assert_eq!(member.visits, 2);
+    }
+}
@@ -0,0 +1,82 @@
+use datalang_runtime::{Record, Value};
+use datalang_tests::*;
+
+fn main() {
+    // This allows running as an example too
+    println!("Run 'cargo test' to execute the actual tests");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_user_field_metadata() {
+        let names: Vec<&str> = User::FIELDS.iter().map(|f| f.name).collect();
+        let terms: Vec<&str> = User::FIELDS.iter().map(|f| f.term).collect();
+
+        // Fields follow the order of the term definition
+        assert_eq!(names, ["name", "lastname", "birthdate"]);
+        assert_eq!(terms, ["Name", "LastName", "BirthDate"]);
+        assert!(User::FIELDS.iter().all(|f| f.dictionary == Some("Base")));
+        assert!(User::FIELDS.iter().all(|f| f.ty == "String"));
+    }
+
+    #[test]
+    fn test_social_media_user_field_origins() {
+        let origins: Vec<(&str, Option<&str>)> = SocialMediaUser::FIELDS
+            .iter()
+            .map(|f| (f.term, f.dictionary))
+            .collect();
+
+        // Namespaced references keep their dictionary, local terms have none declared
+        assert_eq!(
+            origins,
+            [
+                ("Name", Some("Base")),
+                ("BirthDate", Some("Base")),
+                ("Handle", None)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_field_enum_matches_metadata() {
+        assert_eq!(
+            UserField::ALL,
+            [UserField::Name, UserField::LastName, UserField::BirthDate]
+        );
+        assert_eq!(UserField::LastName.info().name, "lastname");
+        assert_eq!(NameField::Name.info().term, "Name");
+    }
+
+    #[test]
+    fn test_get_and_set_through_value() {
+        let mut user = User::new();
+        user.set(UserField::Name, Value::from("John")).unwrap();
+        user.set(UserField::LastName, "Doe".into()).unwrap();
+
+        assert_eq!(user.name, "John");
+        assert_eq!(user.get(UserField::LastName), Value::from("Doe"));
+        assert_eq!(user.get(UserField::BirthDate).as_str(), "");
+    }
+
+    #[test]
+    fn test_generic_field_iteration() {
+        fn describe<R: Record>(record: &R, fields: &[R::Field]) -> Vec<String> {
+            fields
+                .iter()
+                .zip(R::FIELDS)
+                .map(|(field, info)| format!("{}={}", info.name, record.get(*field)))
+                .collect()
+        }
+
+        let mut social_user = SocialMediaUser::new();
+        social_user.handle = "@jane".to_string();
+
+        assert_eq!(
+            describe(&social_user, &SocialMediaUserField::ALL),
+            ["name=", "birthdate=", "handle=@jane"]
+        );
+    }
+}
@@ -0,0 +1,62 @@
+use datalang_runtime::{Fingerprint, Record, StructInfo};
+use datalang_tests::*;
+
+fn main() {
+    // This allows running as an example too
+    println!("Run 'cargo test' to execute the actual tests");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fingerprint_is_stable() {
+        // Pinned values: a change here means consumers built against the old
+        // definitions can no longer match their fingerprints
+        assert_eq!(Fingerprint::new().finish(), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(Fingerprint::new().str("a").finish(), 0x529a_4ddc_8ff5_6bbf);
+        assert_eq!(User::SCHEMA_FINGERPRINT, 0xfbfd_4a32_c1e8_7d95);
+    }
+
+    #[test]
+    fn test_struct_fingerprint_follows_fields() {
+        assert_eq!(User::SCHEMA_FINGERPRINT, User::INFO.fingerprint());
+        assert_ne!(
+            User::SCHEMA_FINGERPRINT,
+            SocialMediaUser::SCHEMA_FINGERPRINT
+        );
+        assert_ne!(Name::SCHEMA_FINGERPRINT, LastName::SCHEMA_FINGERPRINT);
+    }
+
+    #[test]
+    fn test_field_order_changes_fingerprint() {
+        let mut reversed = User::FIELDS.to_vec();
+        reversed.reverse();
+        let reversed: &'static [_] = reversed.leak();
+
+        let info = StructInfo {
+            fields: reversed,
+            ..User::INFO
+        };
+        assert_ne!(info.fingerprint(), User::SCHEMA_FINGERPRINT);
+    }
+
+    #[test]
+    fn test_dictionary_fingerprint() {
+        let expected = [
+            ("BirthDate", BirthDate::SCHEMA_FINGERPRINT),
+            ("LastName", LastName::SCHEMA_FINGERPRINT),
+            ("Name", Name::SCHEMA_FINGERPRINT),
+            ("User", User::SCHEMA_FINGERPRINT),
+        ]
+        .iter()
+        .fold(
+            Fingerprint::new().str("Base"),
+            |hash, (name, fingerprint)| hash.str(name).u64(*fingerprint),
+        )
+        .finish();
+
+        assert_eq!(BASE_SCHEMA_FINGERPRINT, expected);
+    }
+}
@@ -1,5 +1,10 @@
 use datalang_tests::*;
 
+fn main() {
+    // This allows running as an example too
+    println!("Run 'cargo test' to execute the actual tests");
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -13,7 +18,6 @@ mod tests {
         let _handle = Handle::new();
 
         // If compilation succeeds, cross-module usage works
-        assert!(true);
     }
 
     #[test]
@@ -64,7 +68,7 @@ mod tests {
         let _ = social_user.handle;
         // let _ = user.handle; // This should NOT compile
 
-        assert!(true); // Test passes if compilation succeeds
+        // Test passes if compilation succeeds
     }
 
     #[test]
@@ -83,8 +87,3 @@ mod tests {
         assert_ne!(user.name, social_user.name);
     }
 }
-
-fn main() {
-    // This allows running as an example too
-    println!("Run 'cargo test' to execute the actual tests");
-}
@@ -0,0 +1,67 @@
+use datalang_tests::*;
+
+fn main() {
+    // This allows running as an example too
+    println!("Run 'cargo test' to execute the actual tests");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn john() -> User {
+        let mut user = User::new();
+        user.name = "John".to_string();
+        user.lastname = "Doe".to_string();
+        user.birthdate = "1990-01-01".to_string();
+        user
+    }
+
+    #[test]
+    fn test_default_patch_is_empty() {
+        let patch = UserPatch::default();
+        assert!(patch.is_empty());
+
+        let mut user = john();
+        patch.apply(&mut user);
+        assert_eq!(user, john());
+    }
+
+    #[test]
+    fn test_apply_only_touches_set_fields() {
+        let patch = UserPatch {
+            lastname: Some("Smith".to_string()),
+            ..UserPatch::default()
+        };
+        assert!(!patch.is_empty());
+
+        let mut user = john();
+        patch.apply(&mut user);
+
+        assert_eq!(user.name, "John");
+        assert_eq!(user.lastname, "Smith");
+        assert_eq!(user.birthdate, "1990-01-01");
+    }
+
+    #[test]
+    fn test_diff_round_trips() {
+        let old = john();
+        let mut new = john();
+        new.birthdate = "1991-02-02".to_string();
+
+        let patch = UserPatch::diff(&old, &new);
+        assert_eq!(
+            patch,
+            UserPatch {
+                birthdate: Some("1991-02-02".to_string()),
+                ..UserPatch::default()
+            }
+        );
+
+        let mut updated = old.clone();
+        patch.apply(&mut updated);
+        assert_eq!(updated, new);
+
+        assert!(UserPatch::diff(&old, &old).is_empty());
+    }
+}
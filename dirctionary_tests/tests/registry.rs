@@ -0,0 +1,69 @@
+use datalang_runtime::{Record, dictionaries, schema};
+use datalang_tests::*;
+
+fn main() {
+    // This allows running as an example too
+    println!("Run 'cargo test' to execute the actual tests");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dictionaries_are_registered() {
+        let names: Vec<Option<&str>> = dictionaries().iter().map(|d| d.name).collect();
+
+        // Files without a dictionary declaration are collected under `None`
+        assert_eq!(
+            names,
+            [
+                None,
+                Some("Accounts"),
+                Some("Base"),
+                Some("Orders"),
+                Some("Profiles")
+            ]
+        );
+    }
+
+    #[test]
+    fn test_dictionary_lists_terms_and_structs() {
+        let base = schema().dictionary("Base").unwrap();
+        let terms: Vec<&str> = base.terms.iter().map(|t| t.name).collect();
+        let structs: Vec<&str> = base.structs.iter().map(|s| s.name).collect();
+
+        // Simple terms have no struct entry of their own
+        assert_eq!(terms, ["BirthDate", "LastName", "Name", "User"]);
+        assert_eq!(structs, ["User"]);
+        assert!(base.terms.iter().all(|t| t.dictionary == Some("Base")));
+    }
+
+    #[test]
+    fn test_struct_fields_keep_their_origin() {
+        let social_user = schema().find_struct("SocialMediaUser").unwrap();
+        let origins: Vec<(&str, Option<&str>)> = social_user
+            .fields
+            .iter()
+            .map(|f| (f.term, f.dictionary))
+            .collect();
+
+        assert_eq!(social_user.dictionary, None);
+        assert_eq!(social_user.fields, SocialMediaUser::FIELDS);
+        assert_eq!(
+            origins,
+            [
+                ("Name", Some("Base")),
+                ("BirthDate", Some("Base")),
+                ("Handle", None)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_find_term() {
+        let email = schema().find_term("Email").unwrap();
+        assert_eq!(email.dictionary, Some("Accounts"));
+        assert!(schema().find_term("Missing").is_none());
+    }
+}
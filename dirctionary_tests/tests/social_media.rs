@@ -1,5 +1,10 @@
 use datalang_tests::*;
 
+fn main() {
+    // This allows running as an example too
+    println!("Run 'cargo test' to execute the actual tests");
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -37,7 +42,7 @@ mod tests {
         // If lastname field existed, this would cause a compile error:
         // let _ = social_user.lastname; // This line should NOT compile
 
-        assert!(true); // Test passes if compilation succeeds
+        // Test passes if compilation succeeds
     }
 
     #[test]
@@ -50,8 +55,3 @@ mod tests {
         assert_eq!(social_user.handle, "");
     }
 }
-
-fn main() {
-    // This allows running as an example too
-    println!("Run 'cargo test' to execute the actual tests");
-}
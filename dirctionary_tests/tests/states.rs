@@ -0,0 +1,115 @@
+use datalang_runtime::{InvalidTransition, StateMachine};
+use datalang_tests::*;
+
+fn main() {
+    // This allows running as an example too
+    println!("Run 'cargo test' to execute the actual tests");
+}
+
+/// The same syntax through the `datalang!` parser, with `;` between chains
+mod lifecycle {
+    datalang::datalang! {
+        @doc("Lifecycle of an account")
+        term AccountState states { Active -> Suspended -> Active; Active -> Closed; Suspended -> Closed }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lifecycle::AccountState;
+
+    #[test]
+    fn test_states_in_declaration_order() {
+        assert_eq!(
+            OrderStatus::ALL,
+            [
+                OrderStatus::Pending,
+                OrderStatus::Paid,
+                OrderStatus::Shipped,
+                OrderStatus::Cancelled
+            ]
+        );
+        assert_eq!(OrderStatus::default(), OrderStatus::Pending);
+    }
+
+    #[test]
+    fn test_can_transition_to() {
+        assert!(OrderStatus::Pending.can_transition_to(OrderStatus::Paid));
+        assert!(OrderStatus::Pending.can_transition_to(OrderStatus::Cancelled));
+        assert!(!OrderStatus::Paid.can_transition_to(OrderStatus::Cancelled));
+        assert!(!OrderStatus::Shipped.can_transition_to(OrderStatus::Pending));
+    }
+
+    #[test]
+    fn test_typed_transitions() {
+        let status = OrderStatus::default()
+            .to_paid()
+            .unwrap()
+            .to_shipped()
+            .unwrap();
+        assert_eq!(status, OrderStatus::Shipped);
+
+        assert_eq!(
+            status.to_cancelled(),
+            Err(InvalidTransition {
+                machine: "OrderStatus",
+                from: "Shipped",
+                to: "Cancelled"
+            })
+        );
+    }
+
+    #[test]
+    fn test_cycles_and_terminal_states() {
+        let state = AccountState::Active
+            .to_suspended()
+            .unwrap()
+            .to_active()
+            .unwrap();
+        assert_eq!(state, AccountState::Active);
+        assert_eq!(
+            AccountState::Suspended.next_states(),
+            [AccountState::Active, AccountState::Closed]
+        );
+        assert!(AccountState::Closed.is_terminal());
+        assert!(!AccountState::Active.is_terminal());
+    }
+
+    #[test]
+    fn test_text_form() {
+        assert_eq!(OrderStatus::Paid.to_string(), "Paid");
+        assert_eq!("Shipped".parse::<OrderStatus>(), Ok(OrderStatus::Shipped));
+        assert!("Lost".parse::<OrderStatus>().is_err());
+    }
+
+    #[test]
+    fn test_dot_export() {
+        assert_eq!(
+            OrderStatus::to_dot(),
+            "digraph OrderStatus {
+    rankdir=LR;
+    __start [shape=point];
+    __start -> Pending;
+    Pending [shape=circle];
+    Paid [shape=circle];
+    Shipped [shape=doublecircle];
+    Cancelled [shape=doublecircle];
+    Pending -> Paid;
+    Paid -> Shipped;
+    Pending -> Cancelled;
+}
+"
+        );
+    }
+
+    #[test]
+    fn test_registered_as_term() {
+        let orders = datalang_runtime::schema().dictionary("Orders").unwrap();
+        assert!(orders.terms.iter().any(|t| t.name == "OrderStatus"));
+        assert_ne!(
+            OrderStatus::SCHEMA_FINGERPRINT,
+            AccountState::SCHEMA_FINGERPRINT
+        );
+    }
+}
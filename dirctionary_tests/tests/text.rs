@@ -0,0 +1,90 @@
+use datalang_runtime::text::ParseRecordError;
+use datalang_tests::*;
+
+fn main() {
+    // This allows running as an example too
+    println!("Run 'cargo test' to execute the actual tests");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_simple_term_displays_inner_value() {
+        let handle: Handle = "@jane".parse().unwrap();
+
+        assert_eq!(handle.handle, "@jane");
+        assert_eq!(handle.to_string(), "@jane");
+    }
+
+    #[test]
+    fn test_composite_display_follows_definition_order() {
+        let mut user = User::new();
+        user.name = "John".to_string();
+        user.lastname = "Doe".to_string();
+
+        assert_eq!(user.to_string(), "name=John last_name=Doe birth_date=");
+    }
+
+    #[test]
+    fn test_values_are_escaped_and_round_trip() {
+        let mut user = User::new();
+        user.name = "Mary Ann".to_string();
+        user.lastname = "a=b\\c".to_string();
+        user.birthdate = "line\nbreak".to_string();
+
+        let text = user.to_string();
+        assert_eq!(
+            text,
+            r"name=Mary\ Ann last_name=a\=b\\c birth_date=line\nbreak"
+        );
+        assert_eq!(text.parse::<User>().unwrap(), user);
+    }
+
+    #[test]
+    fn test_unicode_whitespace_round_trips() {
+        let mut user = User::new();
+        user.name = "tab\x0Bfeed\x0Cnbsp\u{A0}sep\u{2028}end".to_string();
+        user.lastname = "Doe".to_string();
+
+        let text = user.to_string();
+        assert_eq!(text.split(' ').count(), 3);
+        assert_eq!(text.parse::<User>().unwrap(), user);
+    }
+
+    #[test]
+    fn test_parse_accepts_any_key_order() {
+        let social_user: SocialMediaUser = "handle=@bob  name=Bob birth_date=1990-06-15"
+            .parse()
+            .unwrap();
+
+        assert_eq!(social_user.name, "Bob");
+        assert_eq!(social_user.birthdate, "1990-06-15");
+        assert_eq!(social_user.handle, "@bob");
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(
+            "name=John last_name=Doe".parse::<User>(),
+            Err(ParseRecordError::MissingField("birth_date"))
+        );
+        assert_eq!(
+            "name=John name=Jim last_name= birth_date=".parse::<User>(),
+            Err(ParseRecordError::DuplicateField("name".to_string()))
+        );
+        assert_eq!(
+            "name=John age=3".parse::<User>(),
+            Err(ParseRecordError::UnknownField("age".to_string()))
+        );
+        assert_eq!(
+            "name".parse::<User>(),
+            Err(ParseRecordError::MissingSeparator("name".to_string()))
+        );
+        assert_eq!(
+            "name=John\\".parse::<User>(),
+            Err(ParseRecordError::TrailingEscape)
+        );
+    }
+}
@@ -0,0 +1,106 @@
+use datalang_runtime::Record;
+use datalang_tests::*;
+
+fn main() {
+    // This allows running as an example too
+    println!("Run 'cargo test' to execute the actual tests");
+}
+
+/// The same annotations through the `datalang!` parser
+mod inline {
+    datalang::datalang! {
+        dictionary Inline version 2
+
+        term Title {
+        }
+
+        term Subtitle {
+        }
+
+        @derive(PartialEq)
+        term Book has {
+            +Title
+            @since(2) @default("none") +Subtitle
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_latest_version_is_the_main_struct() {
+        let terms: Vec<&str> = Profile::FIELDS.iter().map(|f| f.term).collect();
+
+        // Nickname was removed in version 3
+        assert_eq!(terms, ["Username", "ContactEmail", "Country"]);
+
+        let profile: ProfileV3 = Profile::with("jdoe");
+        assert_eq!(profile.username, "jdoe");
+    }
+
+    #[test]
+    fn test_defaulted_fields_are_not_arguments() {
+        // Fails to compile if a defaulted field becomes an argument again
+        let with: fn(String) -> Profile = Profile::with;
+        let profile = with("jdoe".to_string());
+
+        assert_eq!(profile.contactemail, "unknown@example.com");
+        assert_eq!(profile.country, "US");
+    }
+
+    #[test]
+    fn test_default_uses_declared_values() {
+        let profile = Profile::default();
+        assert_eq!(profile.username, "");
+        assert_eq!(profile.contactemail, "unknown@example.com");
+        assert_eq!(profile.country, "US");
+
+        assert_eq!(Profile::new(), profile);
+        assert_eq!(ProfileV2::default().contactemail, "unknown@example.com");
+        assert_eq!(inline::Book::default().subtitle, "none");
+    }
+
+    #[test]
+    fn test_older_versions_keep_their_fields() {
+        let v1 = ProfileV1::with("jdoe", "Johnny");
+        let mut v2 = ProfileV2::with("jdoe", "Johnny");
+        v2.contactemail = "j@example.com".to_string();
+
+        assert_eq!(v1.nickname, "Johnny");
+        assert_eq!(v2.contactemail, "j@example.com");
+    }
+
+    #[test]
+    fn test_upgrade_one_version_uses_defaults() {
+        let v2 = ProfileV2::from(ProfileV1::with("jdoe", "Johnny"));
+        assert_eq!(v2.contactemail, "unknown@example.com");
+        assert_eq!(v2, ProfileV2::with("jdoe", "Johnny"));
+
+        let v3 = Profile::from(v2);
+        assert_eq!(v3.country, "US");
+        assert_eq!(v3, Profile::with("jdoe"));
+    }
+
+    #[test]
+    fn test_upgrade_straight_to_latest() {
+        let profile: Profile = ProfileV1::with("jdoe", "Johnny").into();
+        assert_eq!(profile, Profile::with("jdoe"));
+    }
+
+    #[test]
+    fn test_inline_versions() {
+        let book = inline::Book::from(inline::BookV1::with("Dune"));
+        assert_eq!(book.subtitle, "none");
+        assert_eq!(book, inline::Book::with("Dune"));
+    }
+
+    #[test]
+    fn test_upgrade_keeps_existing_values() {
+        let mut v2 = ProfileV2::with("jdoe", "Johnny");
+        v2.contactemail = "j@example.com".to_string();
+        let profile = Profile::from(v2);
+        assert_eq!(profile.contactemail, "j@example.com");
+    }
+}
@@ -34,6 +34,13 @@ datalang! {
     }
 }
 
+// Dictionary terms mixed with Rust-only fields
+#[datalang::entity(+Base::User, -Base::BirthDate)]
+#[derive(Debug)]
+pub struct Account {
+    pub logins: u32,
+}
+
 fn main() {
     println!("=== DataLang Macro Comprehensive Demo ===\n");
 
@@ -76,5 +83,12 @@ fn main() {
     println!("   MinimalUser: {minimal:?}");
     println!("   Note: Only has name field");
 
+    // 5. Entities with handwritten fields
+    println!("\n5. Entities With Handwritten Fields:");
+    let account = Account::with("Alice", "Smith", 3);
+
+    println!("   Account: {account:?}");
+    println!("   Note: Has name and lastname from User, plus logins");
+
     println!("\n✅ All DataLang features working correctly!");
 }
@@ -0,0 +1,127 @@
+// Apache Arrow conversion for generated structs, behind the `arrow` feature
+// Everything here works from `Record::FIELDS`, so every generated or derived struct gets an
+// Arrow schema and record batch conversions without any extra generated code. Columns are
+// named after the Rust fields and carry the term and dictionary they come from as metadata.
+// Private fields cannot be written back through `Record::set`, so they get no column.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+// Re-exported so callers use the same Arrow version as the conversions
+pub use arrow_array;
+pub use arrow_schema;
+
+use arrow_array::{Array, ArrayRef, RecordBatch, StringArray};
+use arrow_schema::{ArrowError, DataType, Field, Schema};
+
+use crate::{FieldInfo, Record, StructInfo, Value};
+
+/// Field metadata key holding the term a column was generated from
+pub const TERM_KEY: &str = "datalang.term";
+/// Field and schema metadata key holding the dictionary of the term or struct
+pub const DICTIONARY_KEY: &str = "datalang.dictionary";
+/// Schema metadata key holding the struct name
+pub const STRUCT_KEY: &str = "datalang.struct";
+
+/// Arrow type of a column holding fields of the given Rust type
+pub fn data_type(ty: &str) -> Result<DataType, ArrowError> {
+    match ty {
+        "String" => Ok(DataType::Utf8),
+        _ => Err(ArrowError::SchemaError(format!(
+            "no Arrow type for fields of type {ty}"
+        ))),
+    }
+}
+
+/// Arrow field for one struct field. Generated structs always hold every field, so
+/// columns are not nullable.
+pub fn field(info: &FieldInfo) -> Result<Field, ArrowError> {
+    let mut metadata = HashMap::from([(TERM_KEY.to_string(), info.term.to_string())]);
+    if let Some(dictionary) = info.dictionary {
+        metadata.insert(DICTIONARY_KEY.to_string(), dictionary.to_string());
+    }
+    Ok(Field::new(info.name, data_type(info.ty)?, false).with_metadata(metadata))
+}
+
+/// Arrow schema with one column per public field of a struct, in definition order
+pub fn schema(info: &StructInfo) -> Result<Schema, ArrowError> {
+    let fields = columns(info.fields)
+        .map(field)
+        .collect::<Result<Vec<_>, _>>()?;
+    let mut metadata = HashMap::from([(STRUCT_KEY.to_string(), info.name.to_string())]);
+    if let Some(dictionary) = info.dictionary {
+        metadata.insert(DICTIONARY_KEY.to_string(), dictionary.to_string());
+    }
+    Ok(Schema::new_with_metadata(fields, metadata))
+}
+
+/// Arrow schema and record batch conversions, implemented for every `Record`
+pub trait ArrowRecord: Record + Sized {
+    /// Arrow schema with one column per public field
+    fn arrow_schema() -> Result<Schema, ArrowError> {
+        schema(&Self::INFO)
+    }
+
+    /// Convert records into one batch, one row per record
+    fn to_record_batch(records: &[Self]) -> Result<RecordBatch, ArrowError> {
+        let mut arrays: Vec<ArrayRef> = Vec::new();
+        for info in columns(Self::FIELDS) {
+            let field = lookup::<Self>(info)?;
+            let values: StringArray = records
+                .iter()
+                .map(|record| Some(String::from(record.get(field))))
+                .collect();
+            arrays.push(Arc::new(values));
+        }
+        RecordBatch::try_new(Arc::new(Self::arrow_schema()?), arrays)
+    }
+
+    /// Read every row of a batch, matching columns to fields by name
+    fn from_record_batch(batch: &RecordBatch) -> Result<Vec<Self>, ArrowError>
+    where
+        Self: Default,
+    {
+        let mut records: Vec<Self> = (0..batch.num_rows()).map(|_| Self::default()).collect();
+        for info in columns(Self::FIELDS) {
+            let field = lookup::<Self>(info)?;
+            let column = batch
+                .column_by_name(info.name)
+                .ok_or_else(|| ArrowError::SchemaError(format!("missing column {}", info.name)))?;
+            let values = column
+                .as_any()
+                .downcast_ref::<StringArray>()
+                .ok_or_else(|| {
+                    ArrowError::SchemaError(format!(
+                        "column {} is {}, expected {}",
+                        info.name,
+                        column.data_type(),
+                        DataType::Utf8
+                    ))
+                })?;
+            if values.null_count() > 0 {
+                return Err(ArrowError::InvalidArgumentError(format!(
+                    "column {} has null values",
+                    info.name
+                )));
+            }
+            for (record, value) in records.iter_mut().zip(values.iter()) {
+                record
+                    .set(field, Value::from(value.unwrap_or_default()))
+                    .map_err(|error| ArrowError::SchemaError(error.to_string()))?;
+            }
+        }
+        Ok(records)
+    }
+}
+
+impl<R: Record> ArrowRecord for R {}
+
+/// Fields that get a column: every field except private ones
+fn columns(fields: &[FieldInfo]) -> impl Iterator<Item = &FieldInfo> {
+    fields.iter().filter(|info| !info.private)
+}
+
+fn lookup<R: Record>(info: &FieldInfo) -> Result<R::Field, ArrowError> {
+    R::field(info.term)
+        .ok_or_else(|| ArrowError::SchemaError(format!("no field for term {}", info.term)))
+}
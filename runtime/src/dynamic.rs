@@ -0,0 +1,176 @@
+// Records whose struct is only known at runtime
+// A `DynRecord` holds one optional value per field of a `StructInfo`, keyed by term.
+// Every value is text and terms declare no constraints, so setting a value only checks that
+// the struct has the term and `validate` checks that every field is set. Generated structs
+// convert to and from it through their `Record` implementation. Private fields cannot be
+// written through `Record::set`, so a record never holds them.
+
+use std::fmt;
+
+use crate::{Record, StructInfo, Value, schema};
+
+/// Error returned when building a `DynRecord` or converting it to a generated struct
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DynRecordError {
+    /// No struct with this name is registered in the schema
+    UnknownStruct(String),
+    /// More than one dictionary declares a struct with this name
+    AmbiguousStruct(String),
+    /// The struct has no field for this term
+    UnknownTerm(String),
+    /// The struct's field for this term is private
+    PrivateTerm(String),
+    /// A field was never set
+    MissingTerm(&'static str),
+    /// The record belongs to a different struct than the conversion target
+    StructMismatch {
+        expected: &'static str,
+        found: &'static str,
+    },
+}
+
+impl fmt::Display for DynRecordError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DynRecordError::UnknownStruct(name) => write!(f, "Unknown struct '{name}'"),
+            DynRecordError::AmbiguousStruct(name) => {
+                write!(f, "Struct '{name}' is declared in more than one dictionary")
+            }
+            DynRecordError::UnknownTerm(term) => write!(f, "Unknown term '{term}'"),
+            DynRecordError::PrivateTerm(term) => write!(f, "Term '{term}' is private"),
+            DynRecordError::MissingTerm(term) => write!(f, "Missing term '{term}'"),
+            DynRecordError::StructMismatch { expected, found } => {
+                write!(f, "Expected a {expected} record but found {found}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for DynRecordError {}
+
+/// Values of one struct, keyed by term and checked against its `StructInfo`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DynRecord {
+    info: StructInfo,
+    /// One slot per field of `info`, in definition order
+    values: Vec<Option<Value>>,
+}
+
+impl DynRecord {
+    /// Create an empty record for a struct
+    pub fn new(info: StructInfo) -> Self {
+        DynRecord {
+            info,
+            values: vec![None; info.fields.len()],
+        }
+    }
+
+    /// Create an empty record for a struct registered in the schema, looked up by
+    /// `Dictionary::Name` or by a `Name` only one dictionary declares
+    pub fn for_struct(name: &str) -> Result<Self, DynRecordError> {
+        let mut matches = schema().structs_named(name);
+        match (matches.next(), matches.next()) {
+            (Some(info), None) => Ok(DynRecord::new(*info)),
+            (None, _) => Err(DynRecordError::UnknownStruct(name.to_string())),
+            (Some(_), Some(_)) => Err(DynRecordError::AmbiguousStruct(name.to_string())),
+        }
+    }
+
+    /// Copy every public field out of a generated struct
+    pub fn from_record<R: Record>(record: &R) -> Self {
+        let values = R::FIELDS
+            .iter()
+            .map(|info| {
+                R::field(info.term)
+                    .filter(|_| !info.private)
+                    .map(|field| record.get(field))
+            })
+            .collect();
+        DynRecord {
+            info: R::INFO,
+            values,
+        }
+    }
+
+    /// Move the values into a generated struct. Every public field must be set.
+    pub fn into_record<R: Record + Default>(self) -> Result<R, DynRecordError> {
+        if self.info.name != R::INFO.name || self.info.dictionary != R::INFO.dictionary {
+            return Err(DynRecordError::StructMismatch {
+                expected: R::INFO.name,
+                found: self.info.name,
+            });
+        }
+
+        let mut record = R::default();
+        for (info, value) in self.info.fields.iter().zip(self.values) {
+            if info.private {
+                continue;
+            }
+            let value = value.ok_or(DynRecordError::MissingTerm(info.term))?;
+            let field =
+                R::field(info.term).ok_or_else(|| DynRecordError::UnknownTerm(info.term.into()))?;
+            record
+                .set(field, value)
+                .map_err(|_| DynRecordError::PrivateTerm(info.term.into()))?;
+        }
+        Ok(record)
+    }
+
+    /// The struct this record holds values for
+    pub fn info(&self) -> &StructInfo {
+        &self.info
+    }
+
+    /// Read the value of a term, if it has been set
+    pub fn get(&self, term: &str) -> Option<&Value> {
+        let index = self.index(term).ok()?;
+        self.values[index].as_ref()
+    }
+
+    /// Set the value of a public term the struct has
+    pub fn set(&mut self, term: &str, value: impl Into<Value>) -> Result<(), DynRecordError> {
+        let index = self.index(term)?;
+        if self.info.fields[index].private {
+            return Err(DynRecordError::PrivateTerm(term.to_string()));
+        }
+        self.values[index] = Some(value.into());
+        Ok(())
+    }
+
+    /// Clear the value of a term
+    pub fn remove(&mut self, term: &str) -> Option<Value> {
+        let index = self.index(term).ok()?;
+        self.values[index].take()
+    }
+
+    /// Check that every public field has a value
+    pub fn validate(&self) -> Result<(), DynRecordError> {
+        let missing = self
+            .info
+            .fields
+            .iter()
+            .zip(&self.values)
+            .position(|(info, value)| !info.private && value.is_none());
+        match missing {
+            Some(index) => Err(DynRecordError::MissingTerm(self.info.fields[index].term)),
+            None => Ok(()),
+        }
+    }
+
+    /// Iterate over the terms that have a value, in definition order
+    pub fn iter(&self) -> impl Iterator<Item = (&'static str, &Value)> {
+        self.info
+            .fields
+            .iter()
+            .zip(&self.values)
+            .filter_map(|(info, value)| value.as_ref().map(|value| (info.term, value)))
+    }
+
+    fn index(&self, term: &str) -> Result<usize, DynRecordError> {
+        self.info
+            .fields
+            .iter()
+            .position(|info| info.term == term)
+            .ok_or_else(|| DynRecordError::UnknownTerm(term.to_string()))
+    }
+}
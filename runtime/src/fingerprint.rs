@@ -0,0 +1,85 @@
+// Stable schema fingerprints
+// Fingerprints are 64-bit FNV-1a hashes over an explicit byte encoding, so they do not
+// depend on the compiler version, the platform or `std::hash`. Every string is prefixed
+// with its length to keep adjacent values from running into each other.
+
+use crate::FieldInfo;
+
+const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const PRIME: u64 = 0x0000_0100_0000_01b3;
+
+/// Incremental fingerprint usable in constant expressions
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Fingerprint(u64);
+
+impl Fingerprint {
+    pub const fn new() -> Self {
+        Fingerprint(OFFSET_BASIS)
+    }
+
+    const fn byte(self, byte: u8) -> Self {
+        Fingerprint((self.0 ^ byte as u64).wrapping_mul(PRIME))
+    }
+
+    /// Add a number as 8 little-endian bytes
+    pub const fn u64(self, value: u64) -> Self {
+        let bytes = value.to_le_bytes();
+        let mut hash = self;
+        let mut index = 0;
+        while index < bytes.len() {
+            hash = hash.byte(bytes[index]);
+            index += 1;
+        }
+        hash
+    }
+
+    /// Add a length-prefixed string
+    pub const fn str(self, value: &str) -> Self {
+        let bytes = value.as_bytes();
+        let mut hash = self.u64(bytes.len() as u64);
+        let mut index = 0;
+        while index < bytes.len() {
+            hash = hash.byte(bytes[index]);
+            index += 1;
+        }
+        hash
+    }
+
+    /// Add an optional string, distinguishing `None` from `Some("")`
+    pub const fn option_str(self, value: Option<&str>) -> Self {
+        match value {
+            Some(value) => self.byte(1).str(value),
+            None => self.byte(0),
+        }
+    }
+
+    /// Add every field's term, dictionary and type, in order
+    pub const fn fields(self, fields: &[FieldInfo]) -> Self {
+        let mut hash = self.u64(fields.len() as u64);
+        let mut index = 0;
+        while index < fields.len() {
+            let field = &fields[index];
+            hash = hash
+                .str(field.term)
+                .option_str(field.dictionary)
+                .str(field.ty);
+            index += 1;
+        }
+        hash
+    }
+
+    pub const fn finish(self) -> u64 {
+        self.0
+    }
+}
+
+impl Default for Fingerprint {
+    fn default() -> Self {
+        Fingerprint::new()
+    }
+}
+
+/// Fingerprint of a struct's resolved fields
+pub const fn fields(fields: &[FieldInfo]) -> u64 {
+    Fingerprint::new().fields(fields).finish()
+}
@@ -0,0 +1,131 @@
+// Runtime support for code generated by the `datalang` macros
+// The `datalang` crate is a proc-macro crate and cannot export types or traits itself,
+// so everything generated code refers to at runtime lives here.
+
+use std::fmt;
+
+#[cfg(feature = "arrow")]
+pub mod arrow;
+pub mod dynamic;
+pub mod fingerprint;
+pub mod schema;
+pub mod state;
+pub mod text;
+
+#[cfg(feature = "arrow")]
+pub use arrow::ArrowRecord;
+pub use dynamic::{DynRecord, DynRecordError};
+pub use fingerprint::Fingerprint;
+pub use schema::{Dictionary, Schema, StructInfo, TermInfo, dictionaries, schema};
+pub use state::{InvalidTransition, StateMachine};
+
+#[doc(hidden)]
+pub mod __private {
+    pub use linkme;
+}
+
+/// Metadata describing one field of a generated struct
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FieldInfo {
+    /// Name of the term the field was generated from
+    pub term: &'static str,
+    /// Dictionary the term comes from, when known
+    pub dictionary: Option<&'static str>,
+    /// Rust field name
+    pub name: &'static str,
+    /// Rust type of the field
+    pub ty: &'static str,
+    /// Declared `private`: `Record::get` reads it but `Record::set` rejects it
+    pub private: bool,
+}
+
+/// A field value read from or written to a generated struct
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Value {
+    Text(String),
+}
+
+impl Value {
+    /// Get the value as a string slice
+    pub fn as_str(&self) -> &str {
+        match self {
+            Value::Text(text) => text,
+        }
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl From<String> for Value {
+    fn from(text: String) -> Self {
+        Value::Text(text)
+    }
+}
+
+impl From<&str> for Value {
+    fn from(text: &str) -> Self {
+        Value::Text(text.to_string())
+    }
+}
+
+impl From<Value> for String {
+    fn from(value: Value) -> Self {
+        match value {
+            Value::Text(text) => text,
+        }
+    }
+}
+
+/// Error returned by `Record::set` for a field declared `private`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PrivateField(pub &'static str);
+
+impl fmt::Display for PrivateField {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Field '{}' is private", self.0)
+    }
+}
+
+impl std::error::Error for PrivateField {}
+
+/// Implemented by the struct generated for every simple term
+pub trait Term {
+    /// Rust type holding the term's value
+    type Value;
+
+    /// Name of the Rust type holding the term's value, as in `FieldInfo::ty`
+    const TYPE: &'static str;
+
+    /// Name of the term
+    const NAME: &'static str;
+
+    /// Dictionary declaring the term, when it has one
+    const DICTIONARY: Option<&'static str>;
+}
+
+/// Field-level access implemented by every struct generated from DataLang terms
+pub trait Record {
+    /// Enum with one variant per field, in definition order
+    type Field: Copy + 'static;
+
+    /// Metadata for every field, in definition order
+    const FIELDS: &'static [FieldInfo];
+
+    /// The struct's name, dictionary and fields
+    const INFO: StructInfo;
+
+    /// Look up the field generated from a term
+    fn field(term: &str) -> Option<Self::Field>;
+
+    /// Read a field
+    fn get(&self, field: Self::Field) -> Value;
+
+    /// Overwrite a field. Private fields are only written by the struct's own code, so they
+    /// are rejected with `PrivateField` and generic loaders such as `FromStr` and
+    /// `TryFrom<DynRecord>` leave them empty.
+    fn set(&mut self, field: Self::Field, value: Value) -> Result<(), PrivateField>;
+}
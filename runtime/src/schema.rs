@@ -0,0 +1,147 @@
+// Global registry of every dictionary compiled into the binary
+// Generated code places a `SchemaItem` into a linker section with `linkme`, so items from
+// every crate are collected without any registration call at startup.
+
+use std::collections::BTreeMap;
+use std::sync::OnceLock;
+
+use crate::FieldInfo;
+
+/// A term declared with `term Name { }` or `term Name has { ... }`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TermInfo {
+    pub name: &'static str,
+    /// Dictionary declaring the term, when the file declares one
+    pub dictionary: Option<&'static str>,
+}
+
+/// A generated or derived struct with its expanded fields
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StructInfo {
+    pub name: &'static str,
+    /// Dictionary declaring the struct, when known
+    pub dictionary: Option<&'static str>,
+    /// Fields in definition order, each with its own dictionary of origin
+    pub fields: &'static [FieldInfo],
+}
+
+/// One registry entry emitted by generated code
+#[derive(Debug, Clone, Copy)]
+pub enum SchemaItem {
+    Term(&'static TermInfo),
+    Struct(&'static StructInfo),
+}
+
+/// Everything registered under one dictionary name
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Dictionary {
+    /// `None` collects items from files without a `dictionary` declaration
+    pub name: Option<&'static str>,
+    pub terms: Vec<&'static TermInfo>,
+    pub structs: Vec<&'static StructInfo>,
+}
+
+/// All dictionaries compiled into the binary, sorted by name
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Schema {
+    pub dictionaries: Vec<Dictionary>,
+}
+
+#[doc(hidden)]
+#[linkme::distributed_slice]
+pub static SCHEMA_ITEMS: [SchemaItem];
+
+impl StructInfo {
+    /// Fingerprint of the struct's fields, equal to its generated `SCHEMA_FINGERPRINT`
+    pub const fn fingerprint(&self) -> u64 {
+        crate::fingerprint::fields(self.fields)
+    }
+}
+
+impl Schema {
+    /// Build a schema from registry entries, grouping them by dictionary.
+    /// Items are sorted by name so the result does not depend on link order.
+    pub fn from_items<'a>(items: impl IntoIterator<Item = &'a SchemaItem>) -> Self {
+        let mut dictionaries: BTreeMap<Option<&'static str>, Dictionary> = BTreeMap::new();
+
+        for item in items {
+            let name = match item {
+                SchemaItem::Term(term) => term.dictionary,
+                SchemaItem::Struct(info) => info.dictionary,
+            };
+            let dictionary = dictionaries.entry(name).or_insert_with(|| Dictionary {
+                name,
+                terms: Vec::new(),
+                structs: Vec::new(),
+            });
+            match item {
+                SchemaItem::Term(term) => dictionary.terms.push(term),
+                SchemaItem::Struct(info) => dictionary.structs.push(info),
+            }
+        }
+
+        let dictionaries = dictionaries
+            .into_values()
+            .map(|mut dictionary| {
+                dictionary.terms.sort_by_key(|term| term.name);
+                dictionary.terms.dedup();
+                dictionary.structs.sort_by_key(|info| info.name);
+                dictionary.structs.dedup();
+                dictionary
+            })
+            .collect();
+
+        Schema { dictionaries }
+    }
+
+    /// Find a dictionary by name
+    pub fn dictionary(&self, name: &str) -> Option<&Dictionary> {
+        self.dictionaries
+            .iter()
+            .find(|dictionary| dictionary.name == Some(name))
+    }
+
+    /// Find a struct by `Dictionary::Name`, or by a `Name` only one dictionary declares
+    pub fn find_struct(&self, name: &str) -> Option<&'static StructInfo> {
+        let mut matches = self.structs_named(name);
+        let info = matches.next()?;
+        matches.next().is_none().then_some(info)
+    }
+
+    /// Every struct matching `Dictionary::Name`, or a bare `Name` in any dictionary
+    pub fn structs_named<'a>(
+        &'a self,
+        name: &'a str,
+    ) -> impl Iterator<Item = &'static StructInfo> + 'a {
+        let (dictionary, name) = match name.split_once("::") {
+            Some((dictionary, name)) => (Some(dictionary), name),
+            None => (None, name),
+        };
+        self.dictionaries
+            .iter()
+            .filter(move |candidate| {
+                dictionary.is_none_or(|dictionary| candidate.name == Some(dictionary))
+            })
+            .flat_map(|dictionary| dictionary.structs.iter().copied())
+            .filter(move |info| info.name == name)
+    }
+
+    /// Find a term by name in any dictionary
+    pub fn find_term(&self, name: &str) -> Option<&'static TermInfo> {
+        self.dictionaries
+            .iter()
+            .flat_map(|dictionary| dictionary.terms.iter().copied())
+            .find(|term| term.name == name)
+    }
+}
+
+/// The schema of every dictionary compiled into the binary
+pub fn schema() -> &'static Schema {
+    static SCHEMA: OnceLock<Schema> = OnceLock::new();
+    SCHEMA.get_or_init(|| Schema::from_items(SCHEMA_ITEMS.iter()))
+}
+
+/// Every dictionary compiled into the binary, with its terms and structs
+pub fn dictionaries() -> &'static [Dictionary] {
+    &schema().dictionaries
+}
@@ -0,0 +1,110 @@
+// Support for state machine terms
+// `term OrderStatus states { Pending -> Paid }` generates an enum implementing
+// `StateMachine`, whose provided methods check transitions and draw the diagram.
+
+use std::fmt;
+
+/// Error returned when a transition is not declared by the state machine
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InvalidTransition {
+    pub machine: &'static str,
+    pub from: &'static str,
+    pub to: &'static str,
+}
+
+impl fmt::Display for InvalidTransition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} cannot move from {} to {}",
+            self.machine, self.from, self.to
+        )
+    }
+}
+
+impl std::error::Error for InvalidTransition {}
+
+/// Error returned when parsing a state name fails
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseStateError {
+    pub machine: &'static str,
+    pub input: String,
+}
+
+impl fmt::Display for ParseStateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "'{}' is not a state of {}", self.input, self.machine)
+    }
+}
+
+impl std::error::Error for ParseStateError {}
+
+/// Implemented by the enum generated for every state machine term
+pub trait StateMachine: Copy + PartialEq + 'static {
+    /// Name of the term
+    const NAME: &'static str;
+
+    /// Every state, starting with the initial one
+    const STATES: &'static [Self];
+
+    /// Every allowed `(from, to)` move
+    const TRANSITIONS: &'static [(Self, Self)];
+
+    /// Name of a state
+    fn name(self) -> &'static str;
+
+    /// Check whether moving from this state to `next` is allowed
+    fn can_transition_to(self, next: Self) -> bool {
+        Self::TRANSITIONS.contains(&(self, next))
+    }
+
+    /// Move to `next`, rejecting moves the state machine does not declare
+    fn transition_to(self, next: Self) -> Result<Self, InvalidTransition> {
+        if self.can_transition_to(next) {
+            Ok(next)
+        } else {
+            Err(InvalidTransition {
+                machine: Self::NAME,
+                from: self.name(),
+                to: next.name(),
+            })
+        }
+    }
+
+    /// States reachable in one move, in declaration order
+    fn next_states(self) -> Vec<Self> {
+        Self::TRANSITIONS
+            .iter()
+            .filter(|(from, _)| *from == self)
+            .map(|(_, to)| *to)
+            .collect()
+    }
+
+    /// Check whether no transition leaves this state
+    fn is_terminal(self) -> bool {
+        !Self::TRANSITIONS.iter().any(|(from, _)| *from == self)
+    }
+
+    /// The state diagram in Graphviz DOT format. The initial state is marked with an
+    /// arrow from a point and terminal states are drawn with a double circle.
+    fn to_dot() -> String {
+        let mut dot = format!("digraph {} {{\n    rankdir=LR;\n", Self::NAME);
+        if let Some(initial) = Self::STATES.first() {
+            dot.push_str("    __start [shape=point];\n");
+            dot.push_str(&format!("    __start -> {};\n", initial.name()));
+        }
+        for state in Self::STATES {
+            let shape = if state.is_terminal() {
+                "doublecircle"
+            } else {
+                "circle"
+            };
+            dot.push_str(&format!("    {} [shape={shape}];\n", state.name()));
+        }
+        for (from, to) in Self::TRANSITIONS {
+            dot.push_str(&format!("    {} -> {};\n", from.name(), to.name()));
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
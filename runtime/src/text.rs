@@ -0,0 +1,107 @@
+// Text form shared by the generated `Display` and `FromStr` implementations
+// Composite structs are written as space separated `field=value` pairs in definition order,
+// leaving out private fields.
+// Backslash escapes every character `char::is_whitespace` matches, `=` and backslash itself
+// inside values, so any string round-trips through `write_pairs` and `parse_pairs`.
+
+use std::fmt;
+
+/// Error returned when parsing the `field=value` form of a generated struct fails
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseRecordError {
+    /// A pair without an `=` separator
+    MissingSeparator(String),
+    /// The input ended in the middle of an escape sequence
+    TrailingEscape,
+    /// A key that is not a field of the struct
+    UnknownField(String),
+    /// The same key appeared more than once
+    DuplicateField(String),
+    /// A field of the struct was not present in the input
+    MissingField(&'static str),
+    /// A key naming a private field, which only the struct's own code writes
+    PrivateField(&'static str),
+}
+
+impl fmt::Display for ParseRecordError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseRecordError::MissingSeparator(pair) => {
+                write!(f, "Expected field=value but found '{pair}'")
+            }
+            ParseRecordError::TrailingEscape => write!(f, "Input ends with an unfinished escape"),
+            ParseRecordError::UnknownField(field) => write!(f, "Unknown field '{field}'"),
+            ParseRecordError::DuplicateField(field) => write!(f, "Field '{field}' appears twice"),
+            ParseRecordError::MissingField(field) => write!(f, "Missing field '{field}'"),
+            ParseRecordError::PrivateField(field) => write!(f, "Field '{field}' is private"),
+        }
+    }
+}
+
+impl std::error::Error for ParseRecordError {}
+
+/// Write `field=value` pairs separated by single spaces, escaping every value
+pub fn write_pairs(f: &mut fmt::Formatter<'_>, pairs: &[(&str, &str)]) -> fmt::Result {
+    for (index, (field, value)) in pairs.iter().enumerate() {
+        if index > 0 {
+            f.write_str(" ")?;
+        }
+        write!(f, "{field}=")?;
+        for c in value.chars() {
+            match c {
+                '\\' => f.write_str("\\\\")?,
+                '=' => f.write_str("\\=")?,
+                '\n' => f.write_str("\\n")?,
+                '\r' => f.write_str("\\r")?,
+                '\t' => f.write_str("\\t")?,
+                // The parser splits pairs on any whitespace, not only ASCII space
+                c if c.is_whitespace() => write!(f, "\\{c}")?,
+                _ => write!(f, "{c}")?,
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Split the output of `write_pairs` back into unescaped `(field, value)` pairs
+pub fn parse_pairs(input: &str) -> Result<Vec<(String, String)>, ParseRecordError> {
+    let mut pairs = Vec::new();
+    let mut chars = input.chars().peekable();
+
+    loop {
+        while chars.next_if(|c| c.is_whitespace()).is_some() {}
+        if chars.peek().is_none() {
+            return Ok(pairs);
+        }
+
+        let mut field = String::new();
+        let mut value = String::new();
+        let mut has_separator = false;
+
+        while let Some(c) = chars.next_if(|c| !c.is_whitespace()) {
+            let target = if has_separator {
+                &mut value
+            } else {
+                &mut field
+            };
+            match c {
+                '\\' => {
+                    let escaped = chars.next().ok_or(ParseRecordError::TrailingEscape)?;
+                    target.push(match escaped {
+                        'n' => '\n',
+                        'r' => '\r',
+                        't' => '\t',
+                        other => other,
+                    });
+                }
+                '=' if !has_separator => has_separator = true,
+                _ => target.push(c),
+            }
+        }
+
+        if !has_separator {
+            return Err(ParseRecordError::MissingSeparator(field));
+        }
+        pairs.push((field, value));
+    }
+}
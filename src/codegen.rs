@@ -0,0 +1,885 @@
+// Code generation for items declared in `datalang!`
+// Every term and struct is first resolved into an `ItemSpec`, then each generator
+// turns the spec into the Rust items it is responsible for.
+
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+use syn::Result;
+
+use crate::entity::generate_terms_macro;
+use crate::states::generate_state_machine;
+use crate::types::{
+    DataLangFile, DataLangItem, Deprecation, ResolvedField, Visibility, snake_case,
+};
+
+/// Everything the generators need to know about one term or struct
+struct ItemSpec<'a> {
+    name: &'a str,
+    /// Dictionary declared by the file, if any
+    dictionary: Option<&'a str>,
+    /// Declared with `term`, as opposed to a plain struct
+    is_term: bool,
+    is_simple: bool,
+    fields: Vec<ResolvedField>,
+    derives: Vec<syn::Path>,
+    visibility: TokenStream,
+    deprecated: Option<Deprecation>,
+    doc: Option<&'a str>,
+}
+
+impl ItemSpec<'_> {
+    fn ident(&self) -> syn::Ident {
+        syn::Ident::new(self.name, Span::call_site())
+    }
+
+    fn suffixed_ident(&self, suffix: &str) -> syn::Ident {
+        syn::Ident::new(&format!("{}{suffix}", self.name), Span::call_site())
+    }
+}
+
+pub fn field_ident(field: &ResolvedField) -> syn::Ident {
+    syn::Ident::new(&field.field_name(), Span::call_site())
+}
+
+/// Whether a generated `with` constructor takes the field. Private fields and fields with a
+/// `@default(...)` are left out, since `with` only takes the terms callers must provide.
+pub fn is_with_field(field: &ResolvedField) -> bool {
+    !field.is_private && field.default.is_none()
+}
+
+/// Initial value of a field: its `@default(...)`, or an empty string
+pub fn default_value(field: &ResolvedField) -> TokenStream {
+    match &field.default {
+        Some(default) => quote! { ::std::string::String::from(#default) },
+        None => quote! { ::core::default::Default::default() },
+    }
+}
+
+/// `with` parameters and the initializer of every field, taken fields moved in and the
+/// others set to their default value
+pub fn with_constructor(fields: &[ResolvedField]) -> (Vec<syn::Ident>, Vec<TokenStream>) {
+    let params = fields
+        .iter()
+        .filter(|field| is_with_field(field))
+        .map(field_ident)
+        .collect();
+    let inits = fields
+        .iter()
+        .map(|field| {
+            let member = field_ident(field);
+            if is_with_field(field) {
+                quote! { #member: #member.into() }
+            } else {
+                let value = default_value(field);
+                quote! { #member: #value }
+            }
+        })
+        .collect();
+    (params, inits)
+}
+
+/// Derives of a generated struct, without `Default`, which `default_impl` writes out
+fn struct_derives(derives: &[syn::Path]) -> Vec<&syn::Path> {
+    derives
+        .iter()
+        .filter(|path| !path.is_ident("Default"))
+        .collect()
+}
+
+/// `impl Default` filling every field with its `@default(...)` value
+fn default_impl(ident: &syn::Ident, fields: &[ResolvedField]) -> TokenStream {
+    let members = fields.iter().map(field_ident);
+    let values = fields.iter().map(default_value);
+    quote! {
+        impl ::core::default::Default for #ident {
+            fn default() -> Self {
+                Self {
+                    #(#members: #values,)*
+                }
+            }
+        }
+    }
+}
+
+fn deprecated_attr(deprecated: Option<&Deprecation>) -> Option<TokenStream> {
+    deprecated.map(|deprecated| {
+        let note = &deprecated.note;
+        quote! { #[deprecated(note = #note)] }
+    })
+}
+
+/// `#[doc = ...]` for text given with `@doc("...")`
+pub fn doc_attr(doc: Option<&str>) -> Option<TokenStream> {
+    doc.map(|doc| quote! { #[doc = #doc] })
+}
+
+/// Validate a parsed file and generate the code for every term and struct in it
+pub fn generate_file(file: &DataLangFile) -> Result<TokenStream> {
+    file.validate()
+        .map_err(|error| syn::Error::new(Span::call_site(), error))?;
+
+    let mut generated_code = Vec::new();
+
+    for item in &file.items {
+        match item {
+            DataLangItem::Dictionary { .. } => {
+                // Dictionary declarations don't generate code directly
+                // In a real implementation, you'd track these for namespace resolution
+            }
+            DataLangItem::Term { name, .. } | DataLangItem::Struct { name, .. } => {
+                // Simple terms expand to themselves, composite terms and structs
+                // to the terms they include
+                generated_code.push(generate_item(file, name)?);
+            }
+            DataLangItem::Import { .. } => {
+                // Import declarations don't generate code directly
+                // In a real implementation, you'd use these for module resolution
+            }
+            DataLangItem::States {
+                name,
+                states,
+                transitions,
+                ..
+            } => {
+                generated_code.push(generate_state_machine(file, name, states, transitions)?);
+            }
+        }
+    }
+
+    generated_code.extend(generate_dictionary_fingerprint(file));
+
+    Ok(quote! {
+        #(#generated_code)*
+    })
+}
+
+/// Generate `<DICTIONARY>_SCHEMA_FINGERPRINT`, combining the fingerprints of every term
+/// and struct in the dictionary by name, so declaration order does not matter
+fn generate_dictionary_fingerprint(file: &DataLangFile) -> Option<TokenStream> {
+    let (dictionary, visibility) = file.items.iter().find_map(|item| match item {
+        DataLangItem::Dictionary {
+            name, visibility, ..
+        } => Some((name, *visibility)),
+        _ => None,
+    })?;
+    let visibility = visibility_tokens(visibility);
+
+    let mut names: Vec<&str> = file
+        .items
+        .iter()
+        .filter_map(|item| match item {
+            DataLangItem::Term { name, .. }
+            | DataLangItem::Struct { name, .. }
+            | DataLangItem::States { name, .. } => Some(name.as_str()),
+            _ => None,
+        })
+        .collect();
+    names.sort_unstable();
+    let idents = names
+        .iter()
+        .map(|name| syn::Ident::new(name, Span::call_site()));
+
+    let const_ident = syn::Ident::new(
+        &format!("{}_SCHEMA_FINGERPRINT", screaming_snake_case(dictionary)),
+        Span::call_site(),
+    );
+    Some(quote! {
+        /// Stable hash of every term and struct in the dictionary
+        #[allow(deprecated)]
+        #visibility const #const_ident: u64 = ::datalang_runtime::Fingerprint::new()
+            .str(#dictionary)
+            #(.str(#names).u64(#idents::SCHEMA_FINGERPRINT))*
+            .finish();
+    })
+}
+
+/// `SocialMedia` -> `SOCIAL_MEDIA`
+fn screaming_snake_case(name: &str) -> String {
+    snake_case(name).to_uppercase()
+}
+
+pub fn visibility_tokens(visibility: Visibility) -> TokenStream {
+    match visibility {
+        Visibility::Crate => quote! { pub(crate) },
+        Visibility::Public | Visibility::Inherited => quote! { pub },
+    }
+}
+
+/// Generate everything a term or struct expands to, honoring its annotations
+fn generate_item(file: &DataLangFile, name: &str) -> Result<TokenStream> {
+    let spec = ItemSpec {
+        name,
+        dictionary: file.dictionary_name(),
+        is_term: file
+            .items
+            .iter()
+            .any(|item| matches!(item, DataLangItem::Term { name: term, .. } if term == name)),
+        is_simple: file.is_simple_term(name),
+        fields: file
+            .expand_fields(name)
+            .map_err(|error| syn::Error::new(Span::call_site(), error))?,
+        derives: derives(file, name)?,
+        visibility: visibility_tokens(file.visibility(name)),
+        deprecated: file.deprecation(name),
+        doc: file.doc(name),
+    };
+
+    let mut code = generate_struct(&spec);
+    code.extend(generate_text(&spec));
+    code.extend(generate_terms_macro(
+        spec.dictionary,
+        spec.name,
+        &spec.fields,
+        file.visibility(name) != Visibility::Crate,
+    ));
+    code.extend(generate_registration(&spec));
+    if file.annotation(name, "patch").is_some() {
+        code.extend(generate_patch(&spec));
+    }
+    if let Some(version) = file.version()
+        && !spec.is_simple
+    {
+        code.extend(generate_versions(file, &spec, version)?);
+    }
+
+    let has_deprecation =
+        spec.deprecated.is_some() || spec.fields.iter().any(|f| f.deprecated.is_some());
+    if has_deprecation {
+        code = allow_deprecated(code, &spec.ident())?;
+    }
+    Ok(code)
+}
+
+/// Mark every generated item except the struct itself `#[allow(deprecated)]`, so only
+/// code written against deprecated items warns, not the code generated for them
+fn allow_deprecated(code: TokenStream, name_ident: &syn::Ident) -> Result<TokenStream> {
+    let mut file: syn::File = syn::parse2(code)?;
+    for item in &mut file.items {
+        let attrs = match item {
+            syn::Item::Struct(item) if item.ident == *name_ident => continue,
+            syn::Item::Const(item) => &mut item.attrs,
+            syn::Item::Enum(item) => &mut item.attrs,
+            syn::Item::Impl(item) => &mut item.attrs,
+            syn::Item::Macro(item) => &mut item.attrs,
+            syn::Item::Struct(item) => &mut item.attrs,
+            syn::Item::Type(item) => &mut item.attrs,
+            _ => continue,
+        };
+        attrs.push(syn::parse_quote!(#[allow(deprecated)]));
+    }
+    Ok(quote! { #file })
+}
+
+/// Traits derived by every generated struct, plus any requested with `@derive(...)`
+fn derives(file: &DataLangFile, name: &str) -> Result<Vec<syn::Path>> {
+    extra_derives(
+        file,
+        name,
+        vec![
+            syn::parse_quote!(Debug),
+            syn::parse_quote!(Clone),
+            syn::parse_quote!(Default),
+        ],
+    )
+}
+
+/// Add the traits requested with `@derive(...)` to `derives`
+pub fn extra_derives(
+    file: &DataLangFile,
+    name: &str,
+    mut derives: Vec<syn::Path>,
+) -> Result<Vec<syn::Path>> {
+    for annotation in file.annotations(name) {
+        if annotation.name != "derive" {
+            continue;
+        }
+        for arg in &annotation.args {
+            let path: syn::Path = syn::parse_str(arg).map_err(|_| {
+                syn::Error::new(
+                    Span::call_site(),
+                    format!("@derive argument '{arg}' on {name} is not a trait path"),
+                )
+            })?;
+            if !contains_path(&derives, &path) {
+                derives.push(path);
+            }
+        }
+    }
+
+    Ok(derives)
+}
+
+fn contains_path(paths: &[syn::Path], path: &syn::Path) -> bool {
+    let path = quote!(#path).to_string();
+    paths
+        .iter()
+        .any(|candidate| quote!(#candidate).to_string() == path)
+}
+
+/// Generate a struct with one `String` field per resolved term, its constructors,
+/// its field enum, its `Record` implementation and its `DynRecord` conversions
+fn generate_struct(spec: &ItemSpec) -> TokenStream {
+    let name_ident = spec.ident();
+    let visibility = &spec.visibility;
+    let derives = &spec.derives;
+
+    let field_idents: Vec<syn::Ident> = spec.fields.iter().map(field_ident).collect();
+    let field_visibilities = spec.fields.iter().map(|f| {
+        if f.is_private {
+            quote! {}
+        } else {
+            quote! { pub }
+        }
+    });
+    // A deprecated or documented simple term is marked as a whole, not through its only field
+    let field_attrs = spec.fields.iter().map(|f| {
+        if spec.is_simple {
+            None
+        } else {
+            let doc = doc_attr(f.doc.as_deref());
+            let deprecated = deprecated_attr(f.deprecated.as_ref());
+            Some(quote! { #doc #deprecated })
+        }
+    });
+    let struct_doc = doc_attr(spec.doc);
+    let struct_attr = deprecated_attr(spec.deprecated.as_ref());
+
+    // Deprecated fields with a replacement can already be read under the new name
+    let forwarding = spec.fields.iter().filter_map(|f| {
+        let replacement = f.deprecated.as_ref()?.replacement.as_deref()?;
+        if spec.is_simple || spec.fields.iter().any(|other| other.term == replacement) {
+            return None;
+        }
+        let field = field_ident(f);
+        let accessor = syn::Ident::new(&replacement.to_lowercase(), Span::call_site());
+        let doc = format!("Read `{}` under its new name", f.field_name());
+        Some(quote! {
+            #[doc = #doc]
+            pub fn #accessor(&self) -> &str {
+                &self.#field
+            }
+        })
+    });
+
+    // Private fields cannot be assigned directly, so expose them read-only. `Record::set` rejects
+    // them too, so only the struct's own code writes them.
+    let getters = spec.fields.iter().filter(|f| f.is_private).map(|f| {
+        let field = field_ident(f);
+        quote! {
+            pub fn #field(&self) -> &str {
+                &self.#field
+            }
+        }
+    });
+
+    // Simple terms can be referenced from `#[derive(DataLang)]` fields
+    let term_impl = spec.is_simple.then(|| {
+        let name = spec.name;
+        let dictionary = option_tokens(spec.fields[0].dictionary.as_deref());
+        let ty = spec.fields[0].rust_type();
+        quote! {
+            impl ::datalang_runtime::Term for #name_ident {
+                type Value = String;
+                const TYPE: &'static str = #ty;
+                const NAME: &'static str = #name;
+                const DICTIONARY: ::core::option::Option<&'static str> = #dictionary;
+            }
+        }
+    });
+
+    let record_fields: Vec<RecordField> = spec.fields.iter().map(record_field).collect();
+    let record = generate_record(&name_ident, spec.dictionary, visibility, &record_fields);
+    let (with_params, with_inits) = with_constructor(&spec.fields);
+    let derives = struct_derives(derives);
+    let default = default_impl(&name_ident, &spec.fields);
+
+    quote! {
+        #struct_doc
+        #[derive(#(#derives),*)]
+        #struct_attr
+        #visibility struct #name_ident {
+            #(#field_attrs #field_visibilities #field_idents: String,)*
+        }
+
+        impl #name_ident {
+            /// The same as `Default::default()`: `@default(...)` values, other fields empty
+            pub fn new() -> Self {
+                Self::default()
+            }
+
+            /// Build the struct from every field without a `@default(...)` except private
+            /// ones, in definition order. The others start at their default value.
+            pub fn with(#(#with_params: impl ::core::convert::Into<String>),*) -> Self {
+                Self {
+                    #(#with_inits,)*
+                }
+            }
+
+            #(#getters)*
+
+            #(#forwarding)*
+        }
+
+        #default
+
+        #term_impl
+
+        #record
+
+        impl ::core::convert::TryFrom<::datalang_runtime::DynRecord> for #name_ident {
+            type Error = ::datalang_runtime::DynRecordError;
+
+            fn try_from(
+                record: ::datalang_runtime::DynRecord,
+            ) -> ::core::result::Result<Self, Self::Error> {
+                record.into_record()
+            }
+        }
+
+        impl ::core::convert::From<#name_ident> for ::datalang_runtime::DynRecord {
+            fn from(value: #name_ident) -> Self {
+                ::datalang_runtime::DynRecord::from_record(&value)
+            }
+        }
+    }
+}
+
+/// One field of a struct implementing `Record`
+pub struct RecordField {
+    /// Variant of the field enum, named after the term
+    pub variant: syn::Ident,
+    /// Rust field holding the value
+    pub member: syn::Ident,
+    pub term: String,
+    /// Constant expression of type `Option<&'static str>`
+    pub dictionary: TokenStream,
+    /// Constant expression of type `&'static str`
+    pub ty: TokenStream,
+    /// `Record::set` rejects the field
+    pub is_private: bool,
+}
+
+/// The `Record` field for a resolved term
+pub fn record_field(field: &ResolvedField) -> RecordField {
+    let ty = field.rust_type();
+    RecordField {
+        variant: syn::Ident::new(&field.term, Span::call_site()),
+        member: field_ident(field),
+        term: field.term.clone(),
+        dictionary: option_tokens(field.dictionary.as_deref()),
+        ty: quote! { #ty },
+        is_private: field.is_private,
+    }
+}
+
+/// Generate the `<Name>Field` enum and the `Record` implementation for a struct
+pub fn generate_record(
+    name_ident: &syn::Ident,
+    dictionary: Option<&str>,
+    visibility: &TokenStream,
+    fields: &[RecordField],
+) -> TokenStream {
+    let field_enum = syn::Ident::new(&format!("{name_ident}Field"), Span::call_site());
+    let field_count = fields.len();
+    let variants: Vec<&syn::Ident> = fields.iter().map(|f| &f.variant).collect();
+    let members: Vec<&syn::Ident> = fields.iter().map(|f| &f.member).collect();
+    let terms: Vec<&str> = fields.iter().map(|f| f.term.as_str()).collect();
+    let name = name_ident.to_string();
+    let dictionary = option_tokens(dictionary);
+
+    let field_infos = fields.iter().map(|f| {
+        let term = &f.term;
+        let field_name = f.member.to_string();
+        let ty = &f.ty;
+        let dictionary = &f.dictionary;
+        let private = f.is_private;
+        quote! {
+            ::datalang_runtime::FieldInfo {
+                term: #term,
+                dictionary: #dictionary,
+                name: #field_name,
+                ty: #ty,
+                private: #private,
+            }
+        }
+    });
+
+    let setters = fields.iter().map(|f| {
+        let variant = &f.variant;
+        let member = &f.member;
+        let term = &f.term;
+        if f.is_private {
+            quote! {
+                #field_enum::#variant => ::core::result::Result::Err(::datalang_runtime::PrivateField(#term)),
+            }
+        } else {
+            quote! {
+                #field_enum::#variant => {
+                    self.#member = value.into();
+                    ::core::result::Result::Ok(())
+                }
+            }
+        }
+    });
+    // `value` goes unused when every field is private
+    let allow_unused = fields
+        .iter()
+        .all(|f| f.is_private)
+        .then(|| quote! { #[allow(unused_variables)] });
+
+    quote! {
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        #visibility enum #field_enum {
+            #(#variants,)*
+        }
+
+        impl #name_ident {
+            /// Stable hash of the resolved terms, their types and their order
+            pub const SCHEMA_FINGERPRINT: u64 = ::datalang_runtime::fingerprint::fields(
+                <Self as ::datalang_runtime::Record>::FIELDS,
+            );
+        }
+
+        impl #field_enum {
+            /// Every field, in definition order
+            pub const ALL: [#field_enum; #field_count] = [#(#field_enum::#variants,)*];
+
+            /// Metadata for this field
+            pub fn info(self) -> &'static ::datalang_runtime::FieldInfo {
+                &<#name_ident as ::datalang_runtime::Record>::FIELDS[self as usize]
+            }
+        }
+
+        impl ::datalang_runtime::Record for #name_ident {
+            type Field = #field_enum;
+
+            const FIELDS: &'static [::datalang_runtime::FieldInfo] = &[#(#field_infos,)*];
+
+            const INFO: ::datalang_runtime::StructInfo = ::datalang_runtime::StructInfo {
+                name: #name,
+                dictionary: #dictionary,
+                fields: Self::FIELDS,
+            };
+
+            fn field(term: &str) -> ::core::option::Option<#field_enum> {
+                match term {
+                    #(#terms => ::core::option::Option::Some(#field_enum::#variants),)*
+                    _ => ::core::option::Option::None,
+                }
+            }
+
+            fn get(&self, field: #field_enum) -> ::datalang_runtime::Value {
+                match field {
+                    #(#field_enum::#variants => ::datalang_runtime::Value::from(self.#members.clone()),)*
+                }
+            }
+
+            #allow_unused
+            fn set(
+                &mut self,
+                field: #field_enum,
+                value: ::datalang_runtime::Value,
+            ) -> ::core::result::Result<(), ::datalang_runtime::PrivateField> {
+                match field {
+                    #(#setters)*
+                }
+            }
+        }
+    }
+}
+
+/// Register a term and, unless it is a simple term, its struct in the runtime schema
+fn generate_registration(spec: &ItemSpec) -> TokenStream {
+    let mut code = TokenStream::new();
+    if spec.is_term {
+        code.extend(register_term(spec.name, spec.dictionary));
+    }
+    if !spec.is_simple {
+        code.extend(register_struct(&spec.ident()));
+    }
+    code
+}
+
+/// Register a term in the runtime schema
+pub fn register_term(name: &str, dictionary: Option<&str>) -> TokenStream {
+    let dictionary = option_tokens(dictionary);
+    schema_item(quote! {
+        ::datalang_runtime::schema::SchemaItem::Term(&::datalang_runtime::TermInfo {
+            name: #name,
+            dictionary: #dictionary,
+        })
+    })
+}
+
+/// Register a struct implementing `Record` in the runtime schema
+pub fn register_struct(name_ident: &syn::Ident) -> TokenStream {
+    schema_item(quote! {
+        ::datalang_runtime::schema::SchemaItem::Struct(
+            &<#name_ident as ::datalang_runtime::Record>::INFO
+        )
+    })
+}
+
+/// Place one entry into the linker section collected by `datalang_runtime::schema()`
+fn schema_item(item: TokenStream) -> TokenStream {
+    quote! {
+        const _: () = {
+            #[::datalang_runtime::__private::linkme::distributed_slice(::datalang_runtime::schema::SCHEMA_ITEMS)]
+            #[linkme(crate = ::datalang_runtime::__private::linkme)]
+            static ITEM: ::datalang_runtime::schema::SchemaItem = #item;
+        };
+    }
+}
+
+pub fn option_tokens(value: Option<&str>) -> TokenStream {
+    match value {
+        Some(value) => quote! { ::core::option::Option::Some(#value) },
+        None => quote! { ::core::option::Option::None },
+    }
+}
+
+/// Generate `<Name>V1` ... `<Name>V<N>` for a dictionary at version N, with `From`
+/// conversions from every version to the next one and to the latest. The latest version
+/// is an alias of the main struct. Fields a version adds are filled from `@default`.
+fn generate_versions(file: &DataLangFile, spec: &ItemSpec, latest: u32) -> Result<TokenStream> {
+    let name_ident = spec.ident();
+    let visibility = &spec.visibility;
+    let derives = &spec.derives;
+    let version_ident = |version: u32| {
+        if version == latest {
+            name_ident.clone()
+        } else {
+            spec.suffixed_ident(&format!("V{version}"))
+        }
+    };
+
+    let mut versions = Vec::new();
+    for version in 1..=latest {
+        let fields = file
+            .expand_fields_at(spec.name, version)
+            .map_err(|error| syn::Error::new(Span::call_site(), error))?;
+        versions.push(fields);
+    }
+
+    let latest_alias = spec.suffixed_ident(&format!("V{latest}"));
+    let mut code = quote! {
+        #visibility type #latest_alias = #name_ident;
+    };
+
+    for (index, fields) in versions.iter().enumerate().take(versions.len() - 1) {
+        let version = index as u32 + 1;
+        let ident = version_ident(version);
+        let field_idents: Vec<syn::Ident> = fields.iter().map(field_ident).collect();
+        let field_visibilities = fields.iter().map(|f| {
+            if f.is_private {
+                quote! {}
+            } else {
+                quote! { pub }
+            }
+        });
+
+        let (with_params, with_inits) = with_constructor(fields);
+        let struct_derives = struct_derives(derives);
+        let default = default_impl(&ident, fields);
+
+        code.extend(quote! {
+            /// Fields of this struct in an older dictionary version
+            #[derive(#(#struct_derives),*)]
+            #visibility struct #ident {
+                #(#field_visibilities #field_idents: String,)*
+            }
+
+            impl #ident {
+                /// Build the struct from every field of this version without a `@default(...)`
+                /// except private ones, in definition order
+                pub fn with(#(#with_params: impl ::core::convert::Into<String>),*) -> Self {
+                    Self {
+                        #(#with_inits,)*
+                    }
+                }
+            }
+
+            #default
+        });
+
+        // One step forward, keeping shared fields and filling new ones
+        let next_ident = version_ident(version + 1);
+        let next_fields = versions[index + 1].iter().map(|next| {
+            let member = field_ident(next);
+            if fields.iter().any(|f| f.term == next.term) {
+                quote! { #member: value.#member }
+            } else if let Some(default) = &next.default {
+                quote! { #member: ::std::string::String::from(#default) }
+            } else {
+                quote! { #member: ::core::default::Default::default() }
+            }
+        });
+        code.extend(quote! {
+            impl ::core::convert::From<#ident> for #next_ident {
+                fn from(value: #ident) -> Self {
+                    Self {
+                        #(#next_fields,)*
+                    }
+                }
+            }
+        });
+
+        // Straight to the latest version through every step in between
+        if version + 1 < latest {
+            code.extend(quote! {
+                impl ::core::convert::From<#ident> for #name_ident {
+                    fn from(value: #ident) -> Self {
+                        Self::from(#next_ident::from(value))
+                    }
+                }
+            });
+        }
+    }
+
+    Ok(code)
+}
+
+/// Generate `Display` and `FromStr`. Simple terms use their value as is, other structs
+/// use the `field=value` form from `datalang_runtime::text` in definition order, keyed by
+/// the snake_case term name. Private fields cannot be parsed back, so they are not written
+/// either.
+fn generate_text(spec: &ItemSpec) -> TokenStream {
+    let name_ident = spec.ident();
+
+    if spec.is_simple {
+        let field = field_ident(&spec.fields[0]);
+        return quote! {
+            impl ::core::fmt::Display for #name_ident {
+                fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                    f.write_str(&self.#field)
+                }
+            }
+
+            impl ::core::str::FromStr for #name_ident {
+                type Err = ::core::convert::Infallible;
+
+                fn from_str(s: &str) -> ::core::result::Result<Self, Self::Err> {
+                    ::core::result::Result::Ok(Self {
+                        #field: s.to_string(),
+                    })
+                }
+            }
+        };
+    }
+
+    let field_enum = spec.suffixed_ident("Field");
+    let field_count = spec.fields.len();
+    let keys: Vec<String> = spec.fields.iter().map(|f| snake_case(&f.term)).collect();
+    let variants = spec
+        .fields
+        .iter()
+        .map(|f| syn::Ident::new(&f.term, Span::call_site()));
+    let written: Vec<&ResolvedField> = spec.fields.iter().filter(|f| !f.is_private).collect();
+    let written_idents = written.iter().map(|f| field_ident(f));
+    let written_keys = written.iter().map(|f| snake_case(&f.term));
+
+    quote! {
+        impl ::core::fmt::Display for #name_ident {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                ::datalang_runtime::text::write_pairs(f, &[#((#written_keys, self.#written_idents.as_str()),)*])
+            }
+        }
+
+        impl ::core::str::FromStr for #name_ident {
+            type Err = ::datalang_runtime::text::ParseRecordError;
+
+            fn from_str(s: &str) -> ::core::result::Result<Self, Self::Err> {
+                const KEYS: [&str; #field_count] = [#(#keys),*];
+                let mut record = Self::new();
+                let mut seen = [false; #field_count];
+
+                for (key, value) in ::datalang_runtime::text::parse_pairs(s)? {
+                    let field = match key.as_str() {
+                        #(#keys => ::core::option::Option::Some(#field_enum::#variants),)*
+                        _ => ::core::option::Option::None,
+                    };
+                    let ::core::option::Option::Some(field) = field else {
+                        return ::core::result::Result::Err(
+                            ::datalang_runtime::text::ParseRecordError::UnknownField(key),
+                        );
+                    };
+                    if ::core::mem::replace(&mut seen[field as usize], true) {
+                        return ::core::result::Result::Err(
+                            ::datalang_runtime::text::ParseRecordError::DuplicateField(key),
+                        );
+                    }
+                    if ::datalang_runtime::Record::set(&mut record, field, value.into()).is_err() {
+                        return ::core::result::Result::Err(
+                            ::datalang_runtime::text::ParseRecordError::PrivateField(
+                                KEYS[field as usize],
+                            ),
+                        );
+                    }
+                }
+
+                let missing = seen
+                    .iter()
+                    .zip(<Self as ::datalang_runtime::Record>::FIELDS)
+                    .position(|(seen, info)| !seen && !info.private);
+                if let ::core::option::Option::Some(missing) = missing {
+                    return ::core::result::Result::Err(
+                        ::datalang_runtime::text::ParseRecordError::MissingField(
+                            KEYS[missing],
+                        ),
+                    );
+                }
+                ::core::result::Result::Ok(record)
+            }
+        }
+    }
+}
+
+/// Generate `<Name>Patch`, a mirror of the struct with every public field optional.
+/// Private fields are left out so patches cannot bypass the struct's constructors.
+fn generate_patch(spec: &ItemSpec) -> TokenStream {
+    let name_ident = spec.ident();
+    let patch_ident = spec.suffixed_ident("Patch");
+    let visibility = &spec.visibility;
+    let field_idents: Vec<syn::Ident> = spec
+        .fields
+        .iter()
+        .filter(|f| !f.is_private)
+        .map(field_ident)
+        .collect();
+
+    let derives = &spec.derives;
+
+    quote! {
+        /// Partial update where `None` leaves the field unchanged
+        #[derive(#(#derives),*)]
+        #visibility struct #patch_ident {
+            #(pub #field_idents: ::core::option::Option<String>,)*
+        }
+
+        impl #patch_ident {
+            /// Overwrite every field that is set in the patch
+            pub fn apply(&self, target: &mut #name_ident) {
+                #(
+                    if let ::core::option::Option::Some(value) = &self.#field_idents {
+                        target.#field_idents = value.clone();
+                    }
+                )*
+            }
+
+            /// Check whether the patch leaves every field unchanged
+            pub fn is_empty(&self) -> bool {
+                true #(&& self.#field_idents.is_none())*
+            }
+
+            /// Build the patch that turns `old` into `new`
+            pub fn diff(old: &#name_ident, new: &#name_ident) -> Self {
+                Self {
+                    #(
+                        #field_idents: (old.#field_idents != new.#field_idents)
+                            .then(|| new.#field_idents.clone()),
+                    )*
+                }
+            }
+        }
+    }
+}
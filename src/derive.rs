@@ -0,0 +1,133 @@
+// `#[derive(DataLang)]` for handwritten structs
+// Fields marked with `#[datalang(term = "...")]` are registered as dictionary terms and get
+// the same field enum and `Record` implementation as structs generated by `datalang!`.
+// The dictionary and type of each field come from the term's `Term` implementation, so the
+// metadata cannot disagree with the term.
+
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+use syn::{Data, DeriveInput, Fields, Result};
+
+use crate::codegen::{RecordField, generate_record, register_struct};
+
+/// Settings read from a `#[datalang(...)]` attribute
+#[derive(Default)]
+struct DataLangAttr {
+    dictionary: Option<String>,
+    term: Option<syn::LitStr>,
+}
+
+fn parse_attrs(attrs: &[syn::Attribute]) -> Result<DataLangAttr> {
+    let mut parsed = DataLangAttr::default();
+
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("datalang")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("dictionary") {
+                parsed.dictionary = Some(meta.value()?.parse::<syn::LitStr>()?.value());
+                Ok(())
+            } else if meta.path.is_ident("term") {
+                parsed.term = Some(meta.value()?.parse()?);
+                Ok(())
+            } else {
+                Err(meta.error("expected `dictionary` or `term`"))
+            }
+        })?;
+    }
+
+    Ok(parsed)
+}
+
+pub fn derive_datalang(input: DeriveInput) -> Result<TokenStream> {
+    let name = &input.ident;
+
+    if !input.generics.params.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &input.generics,
+            "#[derive(DataLang)] does not support generic structs",
+        ));
+    }
+
+    let Data::Struct(data) = &input.data else {
+        return Err(syn::Error::new(
+            Span::call_site(),
+            "#[derive(DataLang)] can only be used on structs",
+        ));
+    };
+    let Fields::Named(named) = &data.fields else {
+        return Err(syn::Error::new(
+            Span::call_site(),
+            "#[derive(DataLang)] needs a struct with named fields",
+        ));
+    };
+
+    let struct_attr = parse_attrs(&input.attrs)?;
+    if struct_attr.term.is_some() {
+        return Err(syn::Error::new_spanned(
+            name,
+            "`term` belongs on fields, use `dictionary` on the struct",
+        ));
+    }
+
+    let mut fields = Vec::new();
+    let mut type_checks = Vec::new();
+
+    for field in &named.named {
+        let field_attr = parse_attrs(&field.attrs)?;
+        if field_attr.dictionary.is_some() {
+            return Err(syn::Error::new_spanned(
+                field,
+                "the dictionary of a field comes from its term, use `dictionary` on the struct",
+            ));
+        }
+        let Some(term) = field_attr.term else {
+            // Fields without a term are plain Rust fields
+            continue;
+        };
+
+        let term_path: syn::Path = term.parse()?;
+        let term_name = term_path
+            .segments
+            .last()
+            .map(|segment| segment.ident.clone())
+            .ok_or_else(|| syn::Error::new_spanned(&term, "expected a term name"))?;
+        let member = field.ident.clone().expect("named fields have identifiers");
+        let ty = &field.ty;
+
+        // The term's generated struct carries its value type, so a mismatch fails to compile
+        type_checks.push(quote! {
+            assert_term_type::<#term_path, #ty>();
+        });
+
+        fields.push(RecordField {
+            variant: term_name.clone(),
+            member,
+            term: term_name.to_string(),
+            dictionary: quote! { <#term_path as ::datalang_runtime::Term>::DICTIONARY },
+            ty: quote! { <#term_path as ::datalang_runtime::Term>::TYPE },
+            is_private: false,
+        });
+    }
+
+    let visibility = &input.vis;
+    let record = generate_record(
+        name,
+        struct_attr.dictionary.as_deref(),
+        &quote!(#visibility),
+        &fields,
+    );
+    let registration = register_struct(name);
+
+    Ok(quote! {
+        const _: () = {
+            fn assert_term_type<T: ::datalang_runtime::Term<Value = V>, V>() {}
+
+            #[allow(dead_code)]
+            fn check_term_types() {
+                #(#type_checks)*
+            }
+        };
+
+        #record
+        #registration
+    })
+}
@@ -0,0 +1,393 @@
+// `#[datalang::entity(...)]` for structs mixing dictionary terms and handwritten fields
+// The attribute cannot see term definitions from other `datalang!` invocations, so every
+// generated term and struct also emits a hidden `macro_rules!` that reports the terms it
+// expands to. The attribute resolves its references one at a time through those macros,
+// threading its state through `__entity_step!` until every reference is expanded. Only
+// public items export their macro, so other crates cannot reach `pub(crate)` terms.
+
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+use syn::parse::{Parse, ParseStream};
+use syn::punctuated::Punctuated;
+use syn::{Result, Token};
+
+use crate::codegen::{
+    RecordField, default_value, field_ident, generate_record, record_field, register_struct,
+    with_constructor,
+};
+use crate::types::{FieldReference, ResolvedField};
+
+/// Name of the hidden macro reporting the terms `namespace::name` expands to
+pub fn terms_macro_ident(namespace: Option<&str>, name: &str) -> syn::Ident {
+    let macro_name = match namespace {
+        Some(namespace) => format!("__datalang_terms_{namespace}_{name}"),
+        None => format!("__datalang_terms_{name}"),
+    };
+    syn::Ident::new(&macro_name, Span::call_site())
+}
+
+/// Generate the hidden macro for one term or struct.
+/// It appends `expanded [(priv Term Dictionary = "default") ...]` to whatever state it is
+/// called with. Unless `exported`, the macro is only visible inside the crate.
+pub fn generate_terms_macro(
+    dictionary: Option<&str>,
+    name: &str,
+    fields: &[ResolvedField],
+    exported: bool,
+) -> TokenStream {
+    let macro_ident = terms_macro_ident(dictionary, name);
+    let leaves = fields.iter().map(|f| {
+        Leaf {
+            term: syn::Ident::new(&f.term, Span::call_site()),
+            dictionary: f
+                .dictionary
+                .as_ref()
+                .map(|dictionary| syn::Ident::new(dictionary, Span::call_site())),
+            default: f
+                .default
+                .as_ref()
+                .map(|default| syn::LitStr::new(default, Span::call_site())),
+            is_private: f.is_private,
+        }
+        .to_tokens()
+    });
+
+    let definition = quote! {
+        macro_rules! #macro_ident {
+            ($($state:tt)*) => {
+                ::datalang::__entity_step! { $($state)* expanded [#(#leaves)*] }
+            };
+        }
+    };
+    if exported {
+        quote! {
+            #[doc(hidden)]
+            #[macro_export]
+            #definition
+        }
+    } else {
+        quote! {
+            #[doc(hidden)]
+            #definition
+            #[doc(hidden)]
+            #[allow(unused_imports)]
+            pub(crate) use #macro_ident;
+        }
+    }
+}
+
+/// Print a reference back into tokens so it can travel through the expansion
+fn reference_tokens(reference: &FieldReference) -> TokenStream {
+    let private = reference
+        .is_private
+        .then(|| syn::Ident::new("private", Span::call_site()));
+    let sign = if reference.is_included {
+        quote! { + }
+    } else {
+        quote! { - }
+    };
+    let name = syn::Ident::new(&reference.name, Span::call_site());
+    match &reference.namespace {
+        Some(namespace) => {
+            let namespace = syn::Ident::new(namespace, Span::call_site());
+            quote! { #private #sign #namespace::#name }
+        }
+        None => quote! { #private #sign #name },
+    }
+}
+
+/// A term reported by a hidden terms macro, or already resolved by a previous step.
+/// Terms are marked `priv` when a `private` reference brought them in, and carry their
+/// `@default(...)` value after `=`.
+struct Leaf {
+    term: syn::Ident,
+    dictionary: Option<syn::Ident>,
+    default: Option<syn::LitStr>,
+    is_private: bool,
+}
+
+impl Parse for Leaf {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let content;
+        syn::parenthesized!(content in input);
+        let is_private = content.peek(Token![priv]);
+        if is_private {
+            content.parse::<Token![priv]>()?;
+        }
+        let term = content.parse()?;
+        let dictionary = if content.peek(syn::Ident) {
+            Some(content.parse()?)
+        } else {
+            None
+        };
+        let default = if content.peek(Token![=]) {
+            content.parse::<Token![=]>()?;
+            Some(content.parse()?)
+        } else {
+            None
+        };
+        Ok(Leaf {
+            term,
+            dictionary,
+            default,
+            is_private,
+        })
+    }
+}
+
+impl Leaf {
+    fn to_tokens(&self) -> TokenStream {
+        let term = &self.term;
+        let dictionary = &self.dictionary;
+        let private = self.is_private.then(|| quote! { priv });
+        let default = self.default.as_ref().map(|default| quote! { = #default });
+        quote! { (#private #term #dictionary #default) }
+    }
+
+    /// The field the term becomes, as `datalang!` would have resolved it
+    fn resolved_field(&self) -> ResolvedField {
+        ResolvedField {
+            term: self.term.to_string(),
+            dictionary: self.dictionary.as_ref().map(ToString::to_string),
+            is_private: self.is_private,
+            since: None,
+            removed: None,
+            default: self.default.as_ref().map(syn::LitStr::value),
+            deprecated: None,
+            doc: None,
+        }
+    }
+}
+
+/// Everything `__entity_step!` needs to continue the expansion
+struct EntityState {
+    current: FieldReference,
+    pending: Vec<FieldReference>,
+    resolved: Vec<Leaf>,
+    item: syn::ItemStruct,
+    expanded: Vec<Leaf>,
+}
+
+fn parse_list<T: Parse>(input: ParseStream) -> Result<Vec<T>> {
+    let content;
+    syn::bracketed!(content in input);
+    let mut values = Vec::new();
+    while !content.is_empty() {
+        values.push(content.parse()?);
+    }
+    Ok(values)
+}
+
+fn expect_keyword(input: ParseStream, keyword: &str) -> Result<()> {
+    let ident: syn::Ident = input.parse()?;
+    if ident != keyword {
+        return Err(syn::Error::new(
+            ident.span(),
+            format!("expected `{keyword}`"),
+        ));
+    }
+    Ok(())
+}
+
+impl Parse for EntityState {
+    fn parse(input: ParseStream) -> Result<Self> {
+        expect_keyword(input, "current")?;
+        let current = {
+            let content;
+            syn::bracketed!(content in input);
+            content.parse()?
+        };
+        expect_keyword(input, "pending")?;
+        let pending = parse_list(input)?;
+        expect_keyword(input, "resolved")?;
+        let resolved = parse_list(input)?;
+        expect_keyword(input, "item")?;
+        let item = {
+            let content;
+            syn::braced!(content in input);
+            content.parse()?
+        };
+        expect_keyword(input, "expanded")?;
+        let expanded = parse_list(input)?;
+
+        Ok(EntityState {
+            current,
+            pending,
+            resolved,
+            item,
+            expanded,
+        })
+    }
+}
+
+/// Start the expansion of `#[entity(...)]`
+pub fn entity_attribute(args: TokenStream, item: syn::ItemStruct) -> Result<TokenStream> {
+    let references = syn::parse::Parser::parse2(
+        Punctuated::<FieldReference, Token![,]>::parse_terminated,
+        args,
+    )?;
+    if let Some(reference) = references.iter().find(|r| !r.annotations.is_empty()) {
+        return Err(syn::Error::new(
+            Span::call_site(),
+            format!(
+                "#[datalang::entity] does not support annotations on {}",
+                reference.full_name()
+            ),
+        ));
+    }
+    continue_expansion(references.into_iter().collect(), Vec::new(), item)
+}
+
+/// Apply the terms reported for the current reference and continue with the next one
+pub fn entity_step(input: TokenStream) -> Result<TokenStream> {
+    let state: EntityState = syn::parse2(input)?;
+    let EntityState {
+        current,
+        pending,
+        mut resolved,
+        item,
+        expanded,
+    } = state;
+
+    if current.is_included {
+        for mut leaf in expanded {
+            leaf.is_private |= current.is_private;
+            if !resolved.iter().any(|existing| existing.term == leaf.term) {
+                resolved.push(leaf);
+            }
+        }
+    } else {
+        resolved.retain(|existing| !expanded.iter().any(|leaf| leaf.term == existing.term));
+    }
+
+    continue_expansion(pending, resolved, item)
+}
+
+fn continue_expansion(
+    mut pending: Vec<FieldReference>,
+    resolved: Vec<Leaf>,
+    item: syn::ItemStruct,
+) -> Result<TokenStream> {
+    if pending.is_empty() {
+        return generate_entity(resolved, item);
+    }
+
+    let current = pending.remove(0);
+    let terms_macro = terms_macro_ident(current.namespace.as_deref(), &current.name);
+    let current_tokens = reference_tokens(&current);
+    let pending_tokens = pending.iter().map(reference_tokens);
+    let resolved_tokens = resolved.iter().map(Leaf::to_tokens);
+
+    Ok(quote! {
+        #terms_macro! {
+            current [#current_tokens]
+            pending [#(#pending_tokens)*]
+            resolved [#(#resolved_tokens)*]
+            item { #item }
+        }
+    })
+}
+
+/// Generate the final struct with term fields first, followed by the handwritten ones
+fn generate_entity(resolved: Vec<Leaf>, item: syn::ItemStruct) -> Result<TokenStream> {
+    let syn::Fields::Named(handwritten) = &item.fields else {
+        return Err(syn::Error::new_spanned(
+            &item.ident,
+            "#[datalang::entity] needs a struct with named fields",
+        ));
+    };
+    if !item.generics.params.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &item.generics,
+            "#[datalang::entity] does not support generic structs",
+        ));
+    }
+
+    let name = &item.ident;
+    let visibility = &item.vis;
+    let attrs = &item.attrs;
+
+    let fields: Vec<ResolvedField> = resolved.iter().map(Leaf::resolved_field).collect();
+    let term_fields: Vec<syn::Ident> = fields.iter().map(field_ident).collect();
+
+    for field in &handwritten.named {
+        if let Some(ident) = &field.ident
+            && term_fields.contains(ident)
+        {
+            return Err(syn::Error::new_spanned(
+                ident,
+                format!("field `{ident}` is already generated from a dictionary term"),
+            ));
+        }
+    }
+
+    let term_visibilities = fields.iter().map(|field| {
+        if field.is_private {
+            quote! {}
+        } else {
+            quote! { #visibility }
+        }
+    });
+    let getters = fields
+        .iter()
+        .zip(&term_fields)
+        .filter(|(field, _)| field.is_private)
+        .map(|(_, field)| {
+            quote! {
+                pub fn #field(&self) -> &str {
+                    &self.#field
+                }
+            }
+        });
+
+    let handwritten_fields = handwritten.named.iter();
+    let handwritten_idents: Vec<&syn::Ident> = handwritten
+        .named
+        .iter()
+        .filter_map(|field| field.ident.as_ref())
+        .collect();
+    let handwritten_types: Vec<&syn::Type> =
+        handwritten.named.iter().map(|field| &field.ty).collect();
+
+    let record_fields: Vec<RecordField> = fields.iter().map(record_field).collect();
+    let defaults = fields.iter().map(default_value);
+    let (with_params, with_inits) = with_constructor(&fields);
+    let record = generate_record(name, None, &quote!(#visibility), &record_fields);
+    let registration = register_struct(name);
+
+    Ok(quote! {
+        #(#attrs)*
+        #visibility struct #name {
+            #(#term_visibilities #term_fields: String,)*
+            #(#handwritten_fields,)*
+        }
+
+        impl #name {
+            /// Build the struct from its handwritten fields, leaving every term at its
+            /// `@default(...)` value or empty
+            pub fn new(#(#handwritten_idents: #handwritten_types),*) -> Self {
+                Self {
+                    #(#term_fields: #defaults,)*
+                    #(#handwritten_idents,)*
+                }
+            }
+
+            /// Build the struct from every term field without a `@default(...)` except
+            /// private ones, followed by every handwritten field
+            pub fn with(
+                #(#with_params: impl ::core::convert::Into<String>,)*
+                #(#handwritten_idents: #handwritten_types,)*
+            ) -> Self {
+                Self {
+                    #(#with_inits,)*
+                    #(#handwritten_idents,)*
+                }
+            }
+
+            #(#getters)*
+        }
+
+        #record
+        #registration
+    })
+}
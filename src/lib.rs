@@ -3,31 +3,95 @@ use quote::quote;
 use syn::parse::{Parse, ParseStream, Result};
 use syn::{DeriveInput, parse_macro_input};
 
+mod codegen;
+mod derive;
+mod entity;
+mod states;
 mod types;
-use types::{DataLangFile, DataLangItem, FieldReference};
+use types::{Annotation, DataLangFile, DataLangItem, FieldReference, Visibility};
 
 impl Parse for DataLangFile {
     fn parse(input: ParseStream) -> Result<Self> {
         let mut items = Vec::new();
 
         while !input.is_empty() {
+            // Annotations apply to the term or struct that follows them
+            let mut annotations = Vec::new();
+            while input.peek(syn::Token![@]) {
+                let annotation: Annotation = input.parse()?;
+                annotations.push(annotation);
+            }
+
+            let visibility = parse_visibility(input)?;
+
             if input.peek(syn::Ident) {
                 let lookahead = input.lookahead1();
                 let fork = input.fork();
                 let first_ident: syn::Ident = fork.parse()?;
 
+                if first_ident == "import" && visibility != Visibility::Inherited {
+                    return Err(syn::Error::new(
+                        first_ident.span(),
+                        "Imports cannot have a visibility modifier",
+                    ));
+                }
+
+                if matches!(first_ident.to_string().as_str(), "dictionary" | "import")
+                    && !annotations.is_empty()
+                {
+                    return Err(syn::Error::new(
+                        first_ident.span(),
+                        "Annotations can only be placed before terms and structs",
+                    ));
+                }
+
                 match first_ident.to_string().as_str() {
                     "dictionary" => {
                         input.parse::<syn::Ident>()?; // consume "dictionary"
                         let name: syn::Ident = input.parse()?;
+
+                        // Optional `version N`
+                        let fork = input.fork();
+                        let version = if fork.parse::<syn::Ident>().is_ok_and(|i| i == "version") {
+                            input.parse::<syn::Ident>()?; // consume "version"
+                            let number: syn::LitInt = input.parse()?;
+                            Some(number.base10_parse::<u32>()?)
+                        } else {
+                            None
+                        };
+
                         items.push(DataLangItem::Dictionary {
                             name: name.to_string(),
+                            visibility,
+                            version,
                         });
                     }
                     "term" => {
                         input.parse::<syn::Ident>()?; // consume "term"
                         let name: syn::Ident = input.parse()?;
 
+                        // term Name states { A -> B; A -> C }
+                        let fork = input.fork();
+                        if fork.parse::<syn::Ident>().is_ok_and(|i| i == "states")
+                            && fork.peek(syn::token::Brace)
+                        {
+                            input.parse::<syn::Ident>()?; // consume "states"
+                            let content;
+                            syn::braced!(content in input);
+                            let chains = parse_state_chains(&content)?;
+                            let (states, transitions) =
+                                types::build_state_machine(&name.to_string(), chains)
+                                    .map_err(|error| syn::Error::new(name.span(), error))?;
+                            items.push(DataLangItem::States {
+                                name: name.to_string(),
+                                visibility,
+                                annotations,
+                                states,
+                                transitions,
+                            });
+                            continue;
+                        }
+
                         // Use proper lookahead to determine term structure
                         let has_fields = if input.peek(syn::Ident) {
                             let fork = input.fork();
@@ -58,7 +122,9 @@ impl Parse for DataLangFile {
 
                             items.push(DataLangItem::Term {
                                 name: name.to_string(),
+                                visibility,
                                 fields,
+                                annotations,
                             });
                         } else if input.peek(syn::token::Brace) {
                             // term Name { }
@@ -66,7 +132,9 @@ impl Parse for DataLangFile {
                             syn::braced!(_content in input);
                             items.push(DataLangItem::Term {
                                 name: name.to_string(),
+                                visibility,
                                 fields: Vec::new(),
+                                annotations,
                             });
                         } else {
                             return Err(input
@@ -132,10 +200,16 @@ impl Parse for DataLangFile {
 
                         items.push(DataLangItem::Struct {
                             name: name.to_string(),
+                            visibility,
                             fields,
+                            annotations,
                         });
                     }
                 }
+            } else if !annotations.is_empty() {
+                return Err(input.error("Expected a term or struct after annotation"));
+            } else if visibility != Visibility::Inherited {
+                return Err(input.error("Expected dictionary, term or struct after visibility"));
             } else {
                 break;
             }
@@ -145,8 +219,107 @@ impl Parse for DataLangFile {
     }
 }
 
+/// Parse `A -> B -> C; A -> D` into one list of states per chain
+fn parse_state_chains(input: ParseStream) -> Result<Vec<Vec<String>>> {
+    let mut chains = Vec::new();
+    while !input.is_empty() {
+        let mut chain = vec![input.parse::<syn::Ident>()?.to_string()];
+        while input.peek(syn::Token![->]) {
+            input.parse::<syn::Token![->]>()?;
+            chain.push(input.parse::<syn::Ident>()?.to_string());
+        }
+        chains.push(chain);
+
+        if !input.is_empty() {
+            input.parse::<syn::Token![;]>()?;
+        }
+    }
+    Ok(chains)
+}
+
+/// Parse an optional `pub`, `pub(crate)` or `internal` modifier
+fn parse_visibility(input: ParseStream) -> Result<Visibility> {
+    if input.peek(syn::Token![pub]) {
+        let visibility: syn::Visibility = input.parse()?;
+        return match visibility {
+            syn::Visibility::Public(_) => Ok(Visibility::Public),
+            syn::Visibility::Restricted(restricted)
+                if restricted.in_token.is_none() && restricted.path.is_ident("crate") =>
+            {
+                Ok(Visibility::Crate)
+            }
+            _ => Err(input.error("Only pub, pub(crate) and internal are supported")),
+        };
+    }
+
+    let fork = input.fork();
+    if fork
+        .parse::<syn::Ident>()
+        .is_ok_and(|ident| ident == "internal")
+    {
+        input.parse::<syn::Ident>()?; // consume "internal"
+        return Ok(Visibility::Crate);
+    }
+
+    Ok(Visibility::Inherited)
+}
+
+impl Parse for Annotation {
+    fn parse(input: ParseStream) -> Result<Self> {
+        input.parse::<syn::Token![@]>()?;
+        let name: syn::Ident = input.parse()?;
+
+        let mut args = Vec::new();
+        if input.peek(syn::token::Paren) {
+            let content;
+            syn::parenthesized!(content in input);
+
+            while !content.is_empty() {
+                if content.peek(syn::LitStr) {
+                    args.push(content.parse::<syn::LitStr>()?.value());
+                } else if content.peek(syn::LitInt) {
+                    args.push(content.parse::<syn::LitInt>()?.base10_digits().to_string());
+                } else {
+                    let path: syn::Path = content.parse()?;
+                    args.push(quote!(#path).to_string().replace(' ', ""));
+                }
+
+                if !content.is_empty() {
+                    content.parse::<syn::Token![,]>()?;
+                }
+            }
+        }
+
+        Ok(Annotation {
+            name: name.to_string(),
+            args,
+        })
+    }
+}
+
 impl Parse for FieldReference {
     fn parse(input: ParseStream) -> Result<Self> {
+        // Parse leading `@since(2)` style annotations
+        let mut annotations = Vec::new();
+        while input.peek(syn::Token![@]) {
+            let annotation: Annotation = input.parse()?;
+            annotations.push(annotation);
+        }
+
+        // Parse the optional `private` modifier
+        let is_private = if input.peek(syn::Ident) {
+            let private: syn::Ident = input.fork().parse()?;
+            if private != "private" {
+                return Err(
+                    input.error("Field references must start with + (include) or - (exclude)")
+                );
+            }
+            input.parse::<syn::Ident>()?; // consume "private"
+            true
+        } else {
+            false
+        };
+
         // Parse + or -
         let is_included = if input.peek(syn::Token![+]) {
             input.parse::<syn::Token![+]>()?;
@@ -158,6 +331,14 @@ impl Parse for FieldReference {
             return Err(input.error("Field references must start with + (include) or - (exclude)"));
         };
 
+        if is_private && !is_included {
+            return Err(input.error("Only included (+) fields can be private"));
+        }
+
+        if !annotations.is_empty() && !is_included {
+            return Err(input.error("Only included (+) fields can be annotated"));
+        }
+
         // Parse field reference (Name or Base::Name)
         if !input.peek(syn::Ident) {
             return Err(input.error("Expected field name after + or -"));
@@ -175,12 +356,16 @@ impl Parse for FieldReference {
                 is_included,
                 namespace: Some(first_part.to_string()),
                 name: second_part.to_string(),
+                is_private,
+                annotations,
             })
         } else {
             Ok(FieldReference {
                 is_included,
                 namespace: None,
                 name: first_part.to_string(),
+                is_private,
+                annotations,
             })
         }
     }
@@ -198,134 +383,90 @@ datalang! {
 pub fn datalang(input: TokenStream) -> TokenStream {
     let parsed = parse_macro_input!(input as DataLangFile);
 
-    let mut generated_code = Vec::new();
-
-    for item in parsed.items {
-        match item {
-            DataLangItem::Dictionary { name } => {
-                // Dictionary declarations don't generate code directly
-                // In a real implementation, you'd track these for namespace resolution
-                println!("cargo:warning=Processing dictionary: {name}");
-            }
-            DataLangItem::Term { name, fields } => {
-                if fields.is_empty() {
-                    // Simple term - generate a basic struct
-                    let name_ident = syn::Ident::new(&name, proc_macro2::Span::call_site());
-                    let snake_name = name.to_lowercase();
-                    let field_name = syn::Ident::new(&snake_name, proc_macro2::Span::call_site());
-
-                    generated_code.push(quote! {
-                        #[derive(Debug, Clone)]
-                        pub struct #name_ident {
-                            pub #field_name: String,
-                        }
-
-                        impl #name_ident {
-                            pub fn new() -> Self {
-                                Self {
-                                    #field_name: String::new(),
-                                }
-                            }
-                        }
-                    });
-                } else {
-                    // Composite term - generate struct with referenced fields
-                    let name_ident = syn::Ident::new(&name, proc_macro2::Span::call_site());
-                    let field_names: Vec<syn::Ident> = fields
-                        .iter()
-                        .filter(|f| f.is_included)
-                        .map(|f| {
-                            // Use the namespace if present for future namespacing logic
-                            let field_name = if let Some(_namespace) = &f.namespace {
-                                // For now, just use the name part, but namespace is available
-                                f.name.to_lowercase()
-                            } else {
-                                f.name.to_lowercase()
-                            };
-                            syn::Ident::new(&field_name, proc_macro2::Span::call_site())
-                        })
-                        .collect();
-
-                    generated_code.push(quote! {
-                        #[derive(Debug, Clone)]
-                        pub struct #name_ident {
-                            #(pub #field_names: String,)*
-                        }
-
-                        impl #name_ident {
-                            pub fn new() -> Self {
-                                Self {
-                                    #(#field_names: String::new(),)*
-                                }
-                            }
-                        }
-                    });
-                }
-            }
-            DataLangItem::Import { module } => {
-                // Import declarations don't generate code directly
-                // In a real implementation, you'd use these for module resolution
-                println!("cargo:warning=Processing import: {module}");
-            }
-            DataLangItem::Struct { name, fields } => {
-                // Regular struct - process field inclusions/exclusions
-                let name_ident = syn::Ident::new(&name, proc_macro2::Span::call_site());
-                let included_fields: Vec<String> = fields
-                    .iter()
-                    .filter(|f| f.is_included)
-                    .map(|f| {
-                        // Use the namespace if present for future namespacing logic
-                        if let Some(_namespace) = &f.namespace {
-                            // For now, just use the name part, but namespace is available
-                            f.name.to_lowercase()
-                        } else {
-                            f.name.to_lowercase()
-                        }
-                    })
-                    .collect();
-
-                let field_idents: Vec<syn::Ident> = included_fields
-                    .iter()
-                    .map(|f| syn::Ident::new(f, proc_macro2::Span::call_site()))
-                    .collect();
-
-                generated_code.push(quote! {
-                    #[derive(Debug, Clone)]
-                    pub struct #name_ident {
-                        #(pub #field_idents: String,)*
-                    }
+    codegen::generate_file(&parsed)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
 
-                    impl #name_ident {
-                        pub fn new() -> Self {
-                            Self {
-                                #(#field_idents: String::new(),)*
-                            }
-                        }
-                    }
-                });
-            }
-        }
-    }
+/// Generate code from a DataLang definition file.
+///
+/// `datalang_file!("defs/base.dl")` reads the file relative to the crate's
+/// `CARGO_MANIFEST_DIR`, parses it like a `.txt` definition and expands it like `datalang!`.
+/// The crate is rebuilt whenever the file changes.
+#[proc_macro]
+pub fn datalang_file(input: TokenStream) -> TokenStream {
+    let path = parse_macro_input!(input as syn::LitStr);
 
-    let expanded = quote! {
-        #(#generated_code)*
-    };
+    expand_file(&path)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
 
-    TokenStream::from(expanded)
+fn expand_file(path: &syn::LitStr) -> Result<proc_macro2::TokenStream> {
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR")
+        .map_err(|_| syn::Error::new(path.span(), "CARGO_MANIFEST_DIR is not set"))?;
+    let full_path = std::path::Path::new(&manifest_dir).join(path.value());
+
+    let content = std::fs::read_to_string(&full_path).map_err(|error| {
+        syn::Error::new(
+            path.span(),
+            format!("Failed to read {}: {error}", full_path.display()),
+        )
+    })?;
+    let parsed = DataLangFile::parse_from_str(&content).map_err(|error| {
+        syn::Error::new(
+            path.span(),
+            format!("Parse error in {}: {error}", full_path.display()),
+        )
+    })?;
+
+    let code = codegen::generate_file(&parsed)?;
+
+    // include_bytes! makes cargo track the file, so edits trigger a rebuild
+    let tracked_path = full_path.to_string_lossy();
+    Ok(quote! {
+        const _: &[u8] = include_bytes!(#tracked_path);
+        #code
+    })
 }
 
-#[proc_macro_derive(DataLang)]
+/// Register a handwritten struct as a dictionary entity.
+///
+/// Fields marked `#[datalang(term = "Name")]` must have the value type of that term and
+/// become part of the struct's `Record` implementation, with the dictionary and value type the
+/// term itself declares. `#[datalang(dictionary = "Base")]` on the struct records which
+/// dictionary the struct belongs to.
+#[proc_macro_derive(DataLang, attributes(datalang))]
 pub fn derive_datalang(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
-    let name = input.ident;
 
-    let expanded = quote! {
-        impl #name {
-            pub fn from_datalang() -> Self {
-                Self::new()
-            }
-        }
-    };
+    derive::derive_datalang(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
 
-    TokenStream::from(expanded)
+/// Build a struct from dictionary terms plus handwritten fields.
+///
+/// `#[datalang::entity(+Base::User, -Base::LastName)] struct Account { retries: u32 }`
+/// injects the resolved term fields ahead of the handwritten ones and generates `new`,
+/// `with` and the `Record` implementation. Terms are looked up through the items generated
+/// by `datalang!`, so they must be in scope: defined earlier in the same module, or
+/// imported from another crate. Terms declared in a dictionary are referenced as
+/// `Dictionary::Term`.
+#[proc_macro_attribute]
+pub fn entity(args: TokenStream, item: TokenStream) -> TokenStream {
+    let item = parse_macro_input!(item as syn::ItemStruct);
+
+    entity::entity_attribute(args.into(), item)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Continue expanding `#[datalang::entity]`, called by the hidden terms macros
+#[doc(hidden)]
+#[proc_macro]
+pub fn __entity_step(input: TokenStream) -> TokenStream {
+    entity::entity_step(input.into())
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
 }
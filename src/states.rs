@@ -0,0 +1,150 @@
+// Code generation for state machine terms
+// `term OrderStatus states { Pending -> Paid; Pending -> Cancelled }` becomes an enum whose
+// first state is the default, with the transition table exposed through
+// `datalang_runtime::StateMachine` and one checked `to_<state>` method per target state.
+
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+use syn::Result;
+
+use crate::codegen::{doc_attr, extra_derives, register_term, visibility_tokens};
+use crate::types::{DataLangFile, snake_case};
+
+pub fn generate_state_machine(
+    file: &DataLangFile,
+    name: &str,
+    states: &[String],
+    transitions: &[(String, String)],
+) -> Result<TokenStream> {
+    let name_ident = syn::Ident::new(name, Span::call_site());
+    let visibility = visibility_tokens(file.visibility(name));
+    let derives = extra_derives(
+        file,
+        name,
+        vec![
+            syn::parse_quote!(Debug),
+            syn::parse_quote!(Clone),
+            syn::parse_quote!(Copy),
+            syn::parse_quote!(PartialEq),
+            syn::parse_quote!(Eq),
+            syn::parse_quote!(Hash),
+            syn::parse_quote!(Default),
+        ],
+    )?;
+
+    let state_idents: Vec<syn::Ident> = states
+        .iter()
+        .map(|state| syn::Ident::new(state, Span::call_site()))
+        .collect();
+    let initial = &state_idents[0];
+    let others = &state_idents[1..];
+    let state_count = states.len();
+
+    let transition_pairs = transitions.iter().map(|(from, to)| {
+        let from = syn::Ident::new(from, Span::call_site());
+        let to = syn::Ident::new(to, Span::call_site());
+        quote! { (#name_ident::#from, #name_ident::#to) }
+    });
+
+    // One checked move per state that some transition leads to
+    let targets = states
+        .iter()
+        .filter(|state| transitions.iter().any(|(_, to)| to == *state));
+    let transition_methods = targets.map(|state| {
+        let method = syn::Ident::new(&format!("to_{}", snake_case(state)), Span::call_site());
+        let state_ident = syn::Ident::new(state, Span::call_site());
+        let doc = format!("Move to `{state}`, if the current state allows it");
+        quote! {
+            #[doc = #doc]
+            pub fn #method(
+                self,
+            ) -> ::core::result::Result<Self, ::datalang_runtime::InvalidTransition> {
+                ::datalang_runtime::StateMachine::transition_to(self, #name_ident::#state_ident)
+            }
+        }
+    });
+
+    let fingerprint_transitions = transitions
+        .iter()
+        .map(|(from, to)| quote! { .str(#from).str(#to) });
+    let transition_count = transitions.len() as u64;
+    let registration = register_term(name, file.dictionary_name());
+    let doc = doc_attr(file.doc(name));
+
+    Ok(quote! {
+        #doc
+        #[derive(#(#derives),*)]
+        #visibility enum #name_ident {
+            #[default]
+            #initial,
+            #(#others,)*
+        }
+
+        impl #name_ident {
+            /// Every state, starting with the initial one
+            pub const ALL: [#name_ident; #state_count] = [#(#name_ident::#state_idents,)*];
+
+            /// Stable hash of the states and transitions
+            pub const SCHEMA_FINGERPRINT: u64 = ::datalang_runtime::Fingerprint::new()
+                .u64(#state_count as u64)
+                #(.str(#states))*
+                .u64(#transition_count)
+                #(#fingerprint_transitions)*
+                .finish();
+
+            /// Check whether moving from this state to `next` is allowed
+            pub fn can_transition_to(self, next: Self) -> bool {
+                ::datalang_runtime::StateMachine::can_transition_to(self, next)
+            }
+
+            /// Move to `next`, rejecting moves the state machine does not declare
+            pub fn transition_to(
+                self,
+                next: Self,
+            ) -> ::core::result::Result<Self, ::datalang_runtime::InvalidTransition> {
+                ::datalang_runtime::StateMachine::transition_to(self, next)
+            }
+
+            /// The state diagram in Graphviz DOT format
+            pub fn to_dot() -> String {
+                <Self as ::datalang_runtime::StateMachine>::to_dot()
+            }
+
+            #(#transition_methods)*
+        }
+
+        impl ::datalang_runtime::StateMachine for #name_ident {
+            const NAME: &'static str = #name;
+            const STATES: &'static [Self] = &Self::ALL;
+            const TRANSITIONS: &'static [(Self, Self)] = &[#(#transition_pairs,)*];
+
+            fn name(self) -> &'static str {
+                match self {
+                    #(#name_ident::#state_idents => #states,)*
+                }
+            }
+        }
+
+        impl ::core::fmt::Display for #name_ident {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                f.write_str(::datalang_runtime::StateMachine::name(*self))
+            }
+        }
+
+        impl ::core::str::FromStr for #name_ident {
+            type Err = ::datalang_runtime::state::ParseStateError;
+
+            fn from_str(s: &str) -> ::core::result::Result<Self, Self::Err> {
+                match s {
+                    #(#states => ::core::result::Result::Ok(#name_ident::#state_idents),)*
+                    _ => ::core::result::Result::Err(::datalang_runtime::state::ParseStateError {
+                        machine: #name,
+                        input: s.to_string(),
+                    }),
+                }
+            }
+        }
+
+        #registration
+    })
+}
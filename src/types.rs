@@ -13,28 +13,117 @@ pub struct FieldReference {
     pub namespace: Option<String>,
     #[allow(dead_code)]
     pub name: String,
+    #[allow(dead_code)]
+    pub is_private: bool, // true when prefixed with `private`
+    #[allow(dead_code)]
+    pub annotations: Vec<Annotation>, // `@since(2)`, `@removed(3)`, `@default("...")`
+}
+
+/// Visibility of a dictionary, term or struct
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Visibility {
+    /// No modifier: items follow their dictionary, dictionaries are public
+    #[default]
+    Inherited,
+    /// `pub`
+    Public,
+    /// `internal` or `pub(crate)`
+    Crate,
 }
 
 #[derive(Debug, Clone)]
 pub enum DataLangItem {
     Dictionary {
         name: String,
+        #[allow(dead_code)]
+        visibility: Visibility,
+        #[allow(dead_code)]
+        version: Option<u32>, // `dictionary Base version 3`
     },
     Term {
         name: String,
         #[allow(dead_code)]
+        visibility: Visibility,
+        #[allow(dead_code)]
         fields: Vec<FieldReference>,
+        #[allow(dead_code)]
+        annotations: Vec<Annotation>,
     },
     Import {
         module: String,
     },
+    /// `term OrderStatus states { Pending -> Paid; Pending -> Cancelled }`
+    States {
+        name: String,
+        #[allow(dead_code)]
+        visibility: Visibility,
+        #[allow(dead_code)]
+        annotations: Vec<Annotation>,
+        #[allow(dead_code)]
+        states: Vec<String>, // in order of first appearance, the first one is the initial state
+        #[allow(dead_code)]
+        transitions: Vec<Transition>,
+    },
     Struct {
         name: String,
         #[allow(dead_code)]
+        visibility: Visibility,
+        #[allow(dead_code)]
         fields: Vec<FieldReference>,
+        #[allow(dead_code)]
+        annotations: Vec<Annotation>,
     },
 }
 
+/// An `@name` or `@name(arg, ...)` annotation placed before a term or struct
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Annotation {
+    pub name: String,
+    #[allow(dead_code)]
+    pub args: Vec<String>,
+}
+
+/// A `From -> To` move of a state machine term
+pub type Transition = (String, String);
+
+/// Annotations understood by the code generator
+#[allow(dead_code)]
+pub const KNOWN_ANNOTATIONS: [&str; 4] = ["derive", "patch", "deprecated", "doc"];
+
+/// Annotations that can be placed before a field reference
+#[allow(dead_code)]
+pub const FIELD_ANNOTATIONS: [&str; 5] = ["since", "removed", "default", "deprecated", "doc"];
+
+/// A term that ends up as a field after `+`/`-` references have been expanded
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResolvedField {
+    #[allow(dead_code)]
+    pub term: String,
+    #[allow(dead_code)]
+    pub dictionary: Option<String>, // dictionary the term comes from, when known
+    #[allow(dead_code)]
+    pub is_private: bool,
+    #[allow(dead_code)]
+    pub since: Option<u32>, // first dictionary version with this field
+    #[allow(dead_code)]
+    pub removed: Option<u32>, // first dictionary version without this field
+    #[allow(dead_code)]
+    pub default: Option<String>, // value used when upgrading from a version without it
+    #[allow(dead_code)]
+    pub deprecated: Option<Deprecation>,
+    #[allow(dead_code)]
+    pub doc: Option<String>, // from `@doc` on the term or the reference bringing it in
+}
+
+/// `@deprecated("note")` or `@deprecated("note", Replacement)` on a term or field reference
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Deprecation {
+    #[allow(dead_code)]
+    pub note: String,
+    #[allow(dead_code)]
+    pub replacement: Option<String>, // term whose name gets a forwarding accessor
+}
+
 #[derive(Debug, Clone)]
 pub struct DataLangFile {
     pub items: Vec<DataLangItem>,
@@ -138,9 +227,39 @@ impl FieldReference {
         self.namespace.as_deref()
     }
 
+    /// Find an annotation placed before this reference
+    #[allow(dead_code)]
+    pub fn annotation(&self, name: &str) -> Option<&Annotation> {
+        self.annotations
+            .iter()
+            .find(|annotation| annotation.name == name)
+    }
+
+    /// Get the version given to `@since` or `@removed`, if it is a valid number
+    #[allow(dead_code)]
+    pub fn version_annotation(&self, name: &str) -> Option<u32> {
+        self.annotation(name)
+            .and_then(|annotation| annotation.args.first())
+            .and_then(|arg| arg.parse().ok())
+    }
+
     #[allow(dead_code)]
     pub fn parse_from_str(input: &str) -> std::result::Result<Self, ParseError> {
-        let trimmed = input.trim();
+        let mut trimmed = input.trim();
+
+        // Parse leading `@since(2)` style annotations
+        let mut annotations = Vec::new();
+        while trimmed.starts_with('@') {
+            let (annotation, rest) = Annotation::parse_prefix(trimmed)?;
+            annotations.push(annotation);
+            trimmed = rest;
+        }
+
+        // Parse the optional `private` modifier
+        let (is_private, trimmed) = match trimmed.strip_prefix("private") {
+            Some(rest) if rest.starts_with(char::is_whitespace) => (true, rest.trim_start()),
+            _ => (false, trimmed),
+        };
 
         // Parse + or -
         let (is_included, rest) = if let Some(rest) = trimmed.strip_prefix('+') {
@@ -154,29 +273,466 @@ impl FieldReference {
             });
         };
 
+        if is_private && !is_included {
+            return Err(ParseError::InvalidFieldReference {
+                field: input.to_string(),
+                reason: "Only included (+) fields can be private".to_string(),
+            });
+        }
+
+        if !annotations.is_empty() && !is_included {
+            return Err(ParseError::InvalidFieldReference {
+                field: input.to_string(),
+                reason: "Only included (+) fields can be annotated".to_string(),
+            });
+        }
+
         // Parse field reference (Name or Base::Name)
         if let Some((namespace, name)) = rest.split_once("::") {
             Ok(FieldReference {
                 is_included,
                 namespace: Some(namespace.trim().to_string()),
                 name: name.trim().to_string(),
+                is_private,
+                annotations,
             })
         } else {
             Ok(FieldReference {
                 is_included,
                 namespace: None,
                 name: rest.to_string(),
+                is_private,
+                annotations,
             })
         }
     }
 }
 
+impl Annotation {
+    /// Parse one annotation from the start of `input`, returning it with the rest of the line
+    #[allow(dead_code)]
+    pub fn parse_prefix(input: &str) -> std::result::Result<(Self, &str), ParseError> {
+        let rest = input
+            .trim_start()
+            .strip_prefix('@')
+            .ok_or_else(|| ParseError::UnexpectedToken(format!("Expected @ in '{input}'")))?;
+
+        let name_len = rest
+            .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+            .unwrap_or(rest.len());
+        if name_len == 0 {
+            return Err(ParseError::MissingIdentifier(
+                "Expected annotation name after @".to_string(),
+            ));
+        }
+        let name = rest[..name_len].to_string();
+        let rest = &rest[name_len..];
+
+        let Some(arg_text) = rest.strip_prefix('(') else {
+            return Ok((
+                Annotation {
+                    name,
+                    args: Vec::new(),
+                },
+                rest.trim(),
+            ));
+        };
+
+        // Split arguments on commas outside of string literals
+        let mut args = Vec::new();
+        let mut current = String::new();
+        let mut in_string = false;
+        let mut escaped = false;
+        let mut close = None;
+        for (index, c) in arg_text.char_indices() {
+            if in_string {
+                match c {
+                    _ if escaped => {
+                        current.push(c);
+                        escaped = false;
+                    }
+                    '\\' => escaped = true,
+                    '"' => in_string = false,
+                    _ => current.push(c),
+                }
+                continue;
+            }
+            match c {
+                '"' => in_string = true,
+                ',' => args.push(std::mem::take(&mut current).trim().to_string()),
+                ')' => {
+                    close = Some(index);
+                    break;
+                }
+                _ if c.is_whitespace() => {}
+                _ => current.push(c),
+            }
+        }
+
+        let Some(close) = close else {
+            return Err(ParseError::InvalidSyntax(format!(
+                "Expected closing parenthesis after @{name} arguments"
+            )));
+        };
+        if !current.trim().is_empty() || !args.is_empty() {
+            args.push(current.trim().to_string());
+        }
+
+        Ok((Annotation { name, args }, arg_text[close + 1..].trim()))
+    }
+}
+
+impl Visibility {
+    /// Parse a `pub`, `pub(crate)` or `internal` modifier
+    #[allow(dead_code)]
+    pub fn from_keyword(keyword: &str) -> Option<Self> {
+        match keyword {
+            "pub" => Some(Visibility::Public),
+            "pub(crate)" | "internal" => Some(Visibility::Crate),
+            _ => None,
+        }
+    }
+}
+
+impl Deprecation {
+    /// Read a `@deprecated` annotation
+    #[allow(dead_code)]
+    pub fn from_annotation(annotation: &Annotation) -> Self {
+        Deprecation {
+            note: annotation.args.first().cloned().unwrap_or_default(),
+            replacement: annotation.args.get(1).cloned(),
+        }
+    }
+}
+
+impl ResolvedField {
+    /// Get the Rust field name generated for this term
+    #[allow(dead_code)]
+    pub fn field_name(&self) -> String {
+        self.term.to_lowercase()
+    }
+
+    /// Get the Rust type generated for this term
+    #[allow(dead_code)]
+    pub fn rust_type(&self) -> &'static str {
+        "String"
+    }
+
+    /// Check whether data may lack the field: a `@default` fills it in, and `@since` or
+    /// `@removed` leave it out of data written by some versions
+    #[allow(dead_code)]
+    pub fn is_optional(&self) -> bool {
+        self.default.is_some() || self.since.is_some() || self.removed.is_some()
+    }
+
+    /// Check whether the field exists in a dictionary version
+    #[allow(dead_code)]
+    pub fn is_alive_at(&self, version: u32) -> bool {
+        self.since.is_none_or(|since| since <= version)
+            && self.removed.is_none_or(|removed| version < removed)
+    }
+}
+
 impl DataLangFile {
+    /// Get the dictionary declared by this file, if any
+    #[allow(dead_code)]
+    pub fn dictionary_name(&self) -> Option<&str> {
+        self.dictionaries().next()
+    }
+
+    /// Get the version declared with `dictionary Name version N`, if any
+    #[allow(dead_code)]
+    pub fn version(&self) -> Option<u32> {
+        self.items.iter().find_map(|item| match item {
+            DataLangItem::Dictionary { version, .. } => *version,
+            _ => None,
+        })
+    }
+
+    /// Check whether `name` is a term without `has` fields
+    #[allow(dead_code)]
+    pub fn is_simple_term(&self, name: &str) -> bool {
+        self.terms()
+            .any(|(term, fields)| term == name && fields.is_empty())
+    }
+
+    /// Expand a term or struct into the flat list of terms it is made of.
+    ///
+    /// Composite terms defined in this file are expanded recursively and `-` references
+    /// remove previously included terms. References to terms defined elsewhere are kept
+    /// as single fields, since their definition is not visible here.
+    ///
+    /// In a versioned dictionary only the fields of the latest version are returned.
+    #[allow(dead_code)]
+    pub fn expand_fields(&self, name: &str) -> std::result::Result<Vec<ResolvedField>, ParseError> {
+        match self.version() {
+            Some(version) => self.expand_fields_at(name, version),
+            None => self.expand_item(name, &mut Vec::new()),
+        }
+    }
+
+    /// Expand a term or struct into the fields it has in one dictionary version
+    #[allow(dead_code)]
+    pub fn expand_fields_at(
+        &self,
+        name: &str,
+        version: u32,
+    ) -> std::result::Result<Vec<ResolvedField>, ParseError> {
+        let mut fields = self.expand_item(name, &mut Vec::new())?;
+        fields.retain(|field| field.is_alive_at(version));
+        Ok(fields)
+    }
+
+    fn expand_item(
+        &self,
+        name: &str,
+        stack: &mut Vec<String>,
+    ) -> std::result::Result<Vec<ResolvedField>, ParseError> {
+        let fields = self
+            .get_fields(name)
+            .ok_or_else(|| ParseError::StructuralError {
+                context: name.to_string(),
+                issue: "no term or struct with this name is defined".to_string(),
+            })?;
+
+        if self.is_simple_term(name) {
+            return Ok(vec![ResolvedField {
+                term: name.to_string(),
+                dictionary: self.dictionary_name().map(str::to_string),
+                is_private: false,
+                since: None,
+                removed: None,
+                default: None,
+                deprecated: self.deprecation(name),
+                doc: self.doc(name).map(str::to_string),
+            }]);
+        }
+
+        if stack.iter().any(|seen| seen == name) {
+            return Err(ParseError::StructuralError {
+                context: name.to_string(),
+                issue: format!("circular reference through {}", stack.join(" -> ")),
+            });
+        }
+        stack.push(name.to_string());
+
+        let mut resolved: Vec<ResolvedField> = Vec::new();
+        for field in fields {
+            let expanded = self.expand_reference(field, stack)?;
+            if field.is_included {
+                for term in expanded {
+                    match resolved.iter().find(|existing| existing.term == term.term) {
+                        Some(existing) if existing.dictionary != term.dictionary => {
+                            return Err(ParseError::StructuralError {
+                                context: name.to_string(),
+                                issue: format!(
+                                    "term {} is included from more than one dictionary",
+                                    term.term
+                                ),
+                            });
+                        }
+                        Some(_) => {}
+                        None => resolved.push(term),
+                    }
+                }
+            } else {
+                resolved.retain(|existing| !expanded.iter().any(|term| term.term == existing.term));
+            }
+        }
+
+        stack.pop();
+        Ok(resolved)
+    }
+
+    fn expand_reference(
+        &self,
+        field: &FieldReference,
+        stack: &mut Vec<String>,
+    ) -> std::result::Result<Vec<ResolvedField>, ParseError> {
+        let is_local = match field.namespace() {
+            Some(namespace) => self.dictionary_name() == Some(namespace),
+            None => true,
+        };
+
+        if is_local && self.state_machine(&field.name).is_some() {
+            return Err(ParseError::InvalidFieldReference {
+                field: field.full_name(),
+                reason: "state machine terms cannot be used as fields".to_string(),
+            });
+        }
+
+        let mut expanded = if is_local && self.get_fields(&field.name).is_some() {
+            self.expand_item(&field.name, stack)?
+        } else {
+            vec![ResolvedField {
+                term: field.name.clone(),
+                dictionary: field.namespace.clone(),
+                is_private: false,
+                since: None,
+                removed: None,
+                default: None,
+                deprecated: None,
+                doc: None,
+            }]
+        };
+
+        // `private +User` makes every field User brings in private, and
+        // `@since(2) +User` adds all of them in version 2
+        let since = field.version_annotation("since");
+        let removed = field.version_annotation("removed");
+        let default = field
+            .annotation("default")
+            .and_then(|annotation| annotation.args.first());
+        let deprecated = field
+            .annotation("deprecated")
+            .map(Deprecation::from_annotation);
+        let doc = field
+            .annotation("doc")
+            .and_then(|annotation| annotation.args.first());
+        for term in &mut expanded {
+            term.is_private |= field.is_private;
+            term.since = term.since.max(since);
+            term.removed = match (term.removed, removed) {
+                (Some(left), Some(right)) => Some(left.min(right)),
+                (left, right) => left.or(right),
+            };
+            if let Some(default) = default {
+                term.default = Some(default.clone());
+            }
+            if let Some(deprecated) = &deprecated {
+                term.deprecated = Some(deprecated.clone());
+            }
+            if let Some(doc) = doc {
+                term.doc = Some(doc.clone());
+            }
+        }
+        Ok(expanded)
+    }
+
+    /// Get the effective visibility of a term or struct, falling back to its dictionary
+    #[allow(dead_code)]
+    pub fn visibility(&self, name: &str) -> Visibility {
+        let dictionary = self
+            .items
+            .iter()
+            .find_map(|item| match item {
+                DataLangItem::Dictionary { visibility, .. } => Some(*visibility),
+                _ => None,
+            })
+            .unwrap_or_default();
+
+        let item = self
+            .items
+            .iter()
+            .find_map(|item| match item {
+                DataLangItem::Term {
+                    name: item_name,
+                    visibility,
+                    ..
+                }
+                | DataLangItem::Struct {
+                    name: item_name,
+                    visibility,
+                    ..
+                }
+                | DataLangItem::States {
+                    name: item_name,
+                    visibility,
+                    ..
+                } if item_name == name => Some(*visibility),
+                _ => None,
+            })
+            .unwrap_or_default();
+
+        match (item, dictionary) {
+            (Visibility::Inherited, Visibility::Crate) => Visibility::Crate,
+            (Visibility::Inherited, _) => Visibility::Public,
+            (item, _) => item,
+        }
+    }
+
+    /// Get the annotations placed before a term or struct
+    #[allow(dead_code)]
+    pub fn annotations(&self, name: &str) -> &[Annotation] {
+        self.items
+            .iter()
+            .find_map(|item| match item {
+                DataLangItem::Term {
+                    name: item_name,
+                    annotations,
+                    ..
+                }
+                | DataLangItem::Struct {
+                    name: item_name,
+                    annotations,
+                    ..
+                }
+                | DataLangItem::States {
+                    name: item_name,
+                    annotations,
+                    ..
+                } if item_name == name => Some(annotations.as_slice()),
+                _ => None,
+            })
+            .unwrap_or(&[])
+    }
+
+    /// Find an annotation by name on a term or struct
+    #[allow(dead_code)]
+    pub fn annotation(&self, name: &str, annotation: &str) -> Option<&Annotation> {
+        self.annotations(name)
+            .iter()
+            .find(|candidate| candidate.name == annotation)
+    }
+
+    /// Get the `@deprecated` annotation of a term or struct
+    #[allow(dead_code)]
+    pub fn deprecation(&self, name: &str) -> Option<Deprecation> {
+        self.annotation(name, "deprecated")
+            .map(Deprecation::from_annotation)
+    }
+
+    /// Get the `@doc` text of a term or struct
+    #[allow(dead_code)]
+    pub fn doc(&self, name: &str) -> Option<&str> {
+        self.annotation(name, "doc")
+            .and_then(|annotation| annotation.args.first())
+            .map(String::as_str)
+    }
+
+    /// Describe every reference to a deprecated term, for build-time warnings
+    #[allow(dead_code)]
+    pub fn deprecation_warnings(&self) -> Vec<String> {
+        let mut warnings = Vec::new();
+        for item in &self.items {
+            let (DataLangItem::Term { name, fields, .. }
+            | DataLangItem::Struct { name, fields, .. }) = item
+            else {
+                continue;
+            };
+            for field in fields.iter().filter(|field| field.is_included) {
+                let is_local = field
+                    .namespace()
+                    .is_none_or(|namespace| self.dictionary_name() == Some(namespace));
+                if !is_local {
+                    continue;
+                }
+                if let Some(deprecation) = self.deprecation(&field.name) {
+                    warnings.push(format!(
+                        "{name} includes deprecated term {}: {}",
+                        field.name, deprecation.note
+                    ));
+                }
+            }
+        }
+        warnings
+    }
+
     /// Get all dictionary items
     #[allow(dead_code)]
     pub fn dictionaries(&self) -> impl Iterator<Item = &str> {
         self.items.iter().filter_map(|item| match item {
-            DataLangItem::Dictionary { name } => Some(name.as_str()),
+            DataLangItem::Dictionary { name, .. } => Some(name.as_str()),
             _ => None,
         })
     }
@@ -185,7 +741,7 @@ impl DataLangFile {
     #[allow(dead_code)]
     pub fn terms(&self) -> impl Iterator<Item = (&str, &[FieldReference])> {
         self.items.iter().filter_map(|item| match item {
-            DataLangItem::Term { name, fields } => Some((name.as_str(), fields.as_slice())),
+            DataLangItem::Term { name, fields, .. } => Some((name.as_str(), fields.as_slice())),
             _ => None,
         })
     }
@@ -194,7 +750,21 @@ impl DataLangFile {
     #[allow(dead_code)]
     pub fn structs(&self) -> impl Iterator<Item = (&str, &[FieldReference])> {
         self.items.iter().filter_map(|item| match item {
-            DataLangItem::Struct { name, fields } => Some((name.as_str(), fields.as_slice())),
+            DataLangItem::Struct { name, fields, .. } => Some((name.as_str(), fields.as_slice())),
+            _ => None,
+        })
+    }
+
+    /// Get the states and transitions of a `term Name states { ... }`
+    #[allow(dead_code)]
+    pub fn state_machine(&self, name: &str) -> Option<(&[String], &[Transition])> {
+        self.items.iter().find_map(|item| match item {
+            DataLangItem::States {
+                name: item_name,
+                states,
+                transitions,
+                ..
+            } if item_name == name => Some((states.as_slice(), transitions.as_slice())),
             _ => None,
         })
     }
@@ -217,7 +787,10 @@ impl DataLangFile {
                 matches!(
                     (item_type, item),
                     ("dictionary", DataLangItem::Dictionary { .. })
-                        | ("term", DataLangItem::Term { .. })
+                        | (
+                            "term",
+                            DataLangItem::Term { .. } | DataLangItem::States { .. }
+                        )
                         | ("struct", DataLangItem::Struct { .. })
                         | ("import", DataLangItem::Import { .. })
                 )
@@ -238,10 +811,12 @@ impl DataLangFile {
             DataLangItem::Term {
                 name: item_name,
                 fields,
+                ..
             } if item_name == name => Some(fields.as_slice()),
             DataLangItem::Struct {
                 name: item_name,
                 fields,
+                ..
             } if item_name == name => Some(fields.as_slice()),
             _ => None,
         })
@@ -250,11 +825,12 @@ impl DataLangFile {
     #[allow(dead_code)]
     pub fn parse_from_str(input: &str) -> std::result::Result<Self, ParseError> {
         let mut items = Vec::new();
+        let mut annotations = Vec::new();
         let lines: Vec<&str> = input.lines().collect();
         let mut i = 0;
 
         while i < lines.len() {
-            let line = lines[i].trim();
+            let mut line = lines[i].trim();
 
             // Skip empty lines and comments
             if line.is_empty() || line.starts_with("//") {
@@ -262,12 +838,49 @@ impl DataLangFile {
                 continue;
             }
 
+            // Annotations apply to the term or struct that follows them
+            while line.starts_with('@') {
+                let (annotation, rest) = Annotation::parse_prefix(line)?;
+                annotations.push(annotation);
+                line = rest;
+            }
+            if line.is_empty() {
+                i += 1;
+                continue;
+            }
+
             let tokens: Vec<&str> = line.split_whitespace().collect();
             if tokens.is_empty() {
                 i += 1;
                 continue;
             }
 
+            // Parse the optional visibility modifier
+            let (visibility, tokens) = match Visibility::from_keyword(tokens[0]) {
+                Some(visibility) if tokens.len() > 1 => (visibility, tokens[1..].to_vec()),
+                Some(_) => {
+                    return Err(ParseError::MissingIdentifier(format!(
+                        "Expected dictionary, term or struct after {}",
+                        tokens[0]
+                    )));
+                }
+                None => (Visibility::Inherited, tokens),
+            };
+
+            if tokens[0] == "import" && visibility != Visibility::Inherited {
+                return Err(ParseError::StructuralError {
+                    context: "import".to_string(),
+                    issue: "imports cannot have a visibility modifier".to_string(),
+                });
+            }
+
+            if matches!(tokens[0], "dictionary" | "import") && !annotations.is_empty() {
+                return Err(ParseError::StructuralError {
+                    context: tokens[0].to_string(),
+                    issue: "annotations can only be placed before terms and structs".to_string(),
+                });
+            }
+
             match tokens[0] {
                 "dictionary" => {
                     if tokens.len() < 2 {
@@ -276,7 +889,24 @@ impl DataLangFile {
                         ));
                     }
                     let name = tokens[1].to_string();
-                    items.push(DataLangItem::Dictionary { name });
+                    let version = match tokens.get(2..) {
+                        Some([]) | None => None,
+                        Some(["version", number]) => Some(number.parse().map_err(|_| {
+                            ParseError::InvalidSyntax(format!(
+                                "Dictionary version must be a positive number, found '{number}'"
+                            ))
+                        })?),
+                        Some(_) => {
+                            return Err(ParseError::InvalidSyntax(format!(
+                                "Expected 'version <number>' after dictionary {name}"
+                            )));
+                        }
+                    };
+                    items.push(DataLangItem::Dictionary {
+                        name,
+                        visibility,
+                        version,
+                    });
                     i += 1;
                 }
                 "import" => {
@@ -297,6 +927,48 @@ impl DataLangFile {
                     }
                     let name = tokens[1].to_string();
 
+                    if tokens.get(2) == Some(&"states") {
+                        // Collect everything between the braces, which may span lines
+                        let mut body = String::new();
+                        let mut opened = false;
+                        let mut closed = false;
+                        let mut j = i;
+                        while j < lines.len() && !closed {
+                            let mut text = if j == i { line } else { lines[j].trim() };
+                            if !opened {
+                                let Some(index) = text.find('{') else {
+                                    j += 1;
+                                    continue;
+                                };
+                                opened = true;
+                                text = &text[index + 1..];
+                            }
+                            if let Some(index) = text.find('}') {
+                                text = &text[..index];
+                                closed = true;
+                            }
+                            body.push_str(text);
+                            body.push('\n');
+                            j += 1;
+                        }
+                        if !closed {
+                            return Err(ParseError::InvalidSyntax(format!(
+                                "Expected {{ ... }} with transitions after term {name} states"
+                            )));
+                        }
+
+                        let (states, transitions) = parse_state_chains(&name, &body)?;
+                        items.push(DataLangItem::States {
+                            name,
+                            visibility,
+                            annotations: std::mem::take(&mut annotations),
+                            states,
+                            transitions,
+                        });
+                        i = j;
+                        continue;
+                    }
+
                     // Look for "has" keyword and opening brace
                     let has_fields = tokens.len() > 2 && tokens[2] == "has";
 
@@ -331,25 +1003,20 @@ impl DataLangFile {
                     let mut fields = Vec::new();
                     if has_fields {
                         let mut field_line = brace_line + 1;
+                        let mut pending = String::new();
                         while field_line < lines.len() {
                             let field_text = lines[field_line].trim();
                             if field_text == "}" {
                                 break;
                             }
-                            if !field_text.is_empty() && !field_text.starts_with("//") {
-                                if field_text.starts_with('+') || field_text.starts_with('-') {
-                                    fields.push(FieldReference::parse_from_str(field_text)?);
-                                } else {
-                                    return Err(ParseError::InvalidFieldReference {
-                                        field: field_text.to_string(),
-                                        reason: "Fields must start with + (include) or - (exclude)"
-                                            .to_string(),
-                                    });
-                                }
-                            }
+                            parse_field_line(field_text, &mut pending, &mut fields)?;
                             field_line += 1;
                         }
+                        finish_fields(&pending)?;
                         i = field_line + 1;
+                    } else if brace_line == i && line.contains('}') {
+                        // Simple term closed on the same line, `term Name {}`
+                        i += 1;
                     } else {
                         // Simple term, find the closing brace
                         let mut close_line = brace_line + 1;
@@ -362,7 +1029,12 @@ impl DataLangFile {
                         i = close_line + 1;
                     }
 
-                    items.push(DataLangItem::Term { name, fields });
+                    items.push(DataLangItem::Term {
+                        name,
+                        visibility,
+                        fields,
+                        annotations: std::mem::take(&mut annotations),
+                    });
                 }
                 _ => {
                     // In DataLang, we only allow valid keywords: dictionary, term, import
@@ -448,31 +1120,35 @@ impl DataLangFile {
                     // Parse fields
                     let mut fields = Vec::new();
                     let mut field_line = brace_line + 1;
+                    let mut pending = String::new();
                     while field_line < lines.len() {
                         let field_text = lines[field_line].trim();
                         if field_text == "}" {
                             break;
                         }
-                        if !field_text.is_empty() && !field_text.starts_with("//") {
-                            if field_text.starts_with('+') || field_text.starts_with('-') {
-                                fields.push(FieldReference::parse_from_str(field_text)?);
-                            } else {
-                                return Err(ParseError::InvalidFieldReference {
-                                    field: field_text.to_string(),
-                                    reason: "Fields must start with + (include) or - (exclude)"
-                                        .to_string(),
-                                });
-                            }
-                        }
+                        parse_field_line(field_text, &mut pending, &mut fields)?;
                         field_line += 1;
                     }
+                    finish_fields(&pending)?;
 
-                    items.push(DataLangItem::Struct { name, fields });
+                    items.push(DataLangItem::Struct {
+                        name,
+                        visibility,
+                        fields,
+                        annotations: std::mem::take(&mut annotations),
+                    });
                     i = field_line + 1;
                 }
             }
         }
 
+        if !annotations.is_empty() {
+            return Err(ParseError::StructuralError {
+                context: format!("@{}", annotations[0].name),
+                issue: "annotation is not followed by a term or struct".to_string(),
+            });
+        }
+
         Ok(DataLangFile { items })
     }
 
@@ -481,12 +1157,17 @@ impl DataLangFile {
         // Basic validation - ensure no empty names
         for item in &self.items {
             match item {
-                DataLangItem::Dictionary { name } => {
+                DataLangItem::Dictionary { name, version, .. } => {
                     if name.is_empty() {
                         return Err(ParseError::InvalidSyntax(
                             "Dictionary name cannot be empty".to_string(),
                         ));
                     }
+                    if *version == Some(0) {
+                        return Err(ParseError::InvalidSyntax(format!(
+                            "Dictionary {name} version must start at 1"
+                        )));
+                    }
                 }
                 DataLangItem::Term { name, .. } => {
                     if name.is_empty() {
@@ -494,6 +1175,9 @@ impl DataLangFile {
                             "Term name cannot be empty".to_string(),
                         ));
                     }
+                    self.expand_fields(name)?;
+                    self.validate_annotations(name)?;
+                    self.validate_field_annotations(name)?;
                 }
                 DataLangItem::Import { module } => {
                     if module.is_empty() {
@@ -502,15 +1186,272 @@ impl DataLangFile {
                         ));
                     }
                 }
+                DataLangItem::States { name, .. } => {
+                    if name.is_empty() {
+                        return Err(ParseError::InvalidSyntax(
+                            "Term name cannot be empty".to_string(),
+                        ));
+                    }
+                    let unsupported = self
+                        .annotations(name)
+                        .iter()
+                        .find(|annotation| !matches!(annotation.name.as_str(), "derive" | "doc"));
+                    if let Some(annotation) = unsupported {
+                        return Err(ParseError::StructuralError {
+                            context: name.to_string(),
+                            issue: format!(
+                                "@{} cannot be used on a state machine term, only @derive and @doc",
+                                annotation.name
+                            ),
+                        });
+                    }
+                    self.validate_annotations(name)?;
+                }
                 DataLangItem::Struct { name, .. } => {
                     if name.is_empty() {
                         return Err(ParseError::InvalidSyntax(
                             "Struct name cannot be empty".to_string(),
                         ));
                     }
+                    self.expand_fields(name)?;
+                    self.validate_annotations(name)?;
+                    self.validate_field_annotations(name)?;
                 }
             }
         }
         Ok(())
     }
+
+    fn validate_field_annotations(&self, name: &str) -> std::result::Result<(), ParseError> {
+        let version = self.version();
+        for field in self.get_fields(name).unwrap_or(&[]) {
+            for annotation in &field.annotations {
+                let issue = match annotation.name.as_str() {
+                    "since" | "removed" => {
+                        let number = match annotation.args.as_slice() {
+                            [arg] => arg.parse::<u32>().ok().filter(|number| *number > 0),
+                            _ => None,
+                        };
+                        match (number, version) {
+                            (None, _) => {
+                                Some(format!("@{} takes one version number", annotation.name))
+                            }
+                            (Some(_), None) => Some(format!(
+                                "@{} needs a dictionary version, e.g. `dictionary Name version 2`",
+                                annotation.name
+                            )),
+                            (Some(number), Some(version)) if number > version => Some(format!(
+                                "@{}({number}) is later than the dictionary version {version}",
+                                annotation.name
+                            )),
+                            _ => None,
+                        }
+                    }
+                    "default" if annotation.args.len() != 1 => {
+                        Some("@default takes one value".to_string())
+                    }
+                    "deprecated" => deprecation_issue(annotation),
+                    "doc" if annotation.args.len() != 1 => {
+                        Some("@doc takes one string".to_string())
+                    }
+                    known if FIELD_ANNOTATIONS.contains(&known) => None,
+                    unknown => Some(format!(
+                        "unknown field annotation @{unknown}, expected one of: {}",
+                        FIELD_ANNOTATIONS.join(", ")
+                    )),
+                };
+
+                if let Some(issue) = issue {
+                    return Err(ParseError::InvalidFieldReference {
+                        field: field.full_name(),
+                        reason: issue,
+                    });
+                }
+            }
+
+            if let (Some(since), Some(removed)) = (
+                field.version_annotation("since"),
+                field.version_annotation("removed"),
+            ) && since >= removed
+            {
+                return Err(ParseError::InvalidFieldReference {
+                    field: field.full_name(),
+                    reason: format!("@since({since}) must come before @removed({removed})"),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    fn validate_annotations(&self, name: &str) -> std::result::Result<(), ParseError> {
+        for annotation in self.annotations(name) {
+            let issue = match annotation.name.as_str() {
+                "derive" if annotation.args.is_empty() => {
+                    Some("@derive needs at least one trait name".to_string())
+                }
+                "patch" if !annotation.args.is_empty() => {
+                    Some("@patch does not take arguments".to_string())
+                }
+                "deprecated" => deprecation_issue(annotation),
+                "doc" if annotation.args.len() != 1 => Some("@doc takes one string".to_string()),
+                known if KNOWN_ANNOTATIONS.contains(&known) => None,
+                unknown => Some(format!(
+                    "unknown annotation @{unknown}, expected one of: {}",
+                    KNOWN_ANNOTATIONS.join(", ")
+                )),
+            };
+
+            if let Some(issue) = issue {
+                return Err(ParseError::StructuralError {
+                    context: name.to_string(),
+                    issue,
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Parse one line of a `{ ... }` field list. Lines holding only annotations are kept in
+/// `pending` and applied to the next field reference.
+#[allow(dead_code)]
+fn parse_field_line(
+    field_text: &str,
+    pending: &mut String,
+    fields: &mut Vec<FieldReference>,
+) -> std::result::Result<(), ParseError> {
+    if field_text.is_empty() || field_text.starts_with("//") {
+        return Ok(());
+    }
+
+    let mut reference = field_text;
+    while reference.starts_with('@') {
+        let (_, rest) = Annotation::parse_prefix(reference)?;
+        reference = rest;
+    }
+    if reference.is_empty() {
+        pending.push_str(field_text);
+        pending.push(' ');
+        return Ok(());
+    }
+
+    if !(reference.starts_with('+')
+        || reference.starts_with('-')
+        || reference.starts_with("private "))
+    {
+        return Err(ParseError::InvalidFieldReference {
+            field: field_text.to_string(),
+            reason: "Fields must start with + (include) or - (exclude)".to_string(),
+        });
+    }
+
+    let line = format!("{pending}{field_text}");
+    pending.clear();
+    fields.push(FieldReference::parse_from_str(&line)?);
+    Ok(())
+}
+
+/// Reject annotations left over at the end of a field list
+#[allow(dead_code)]
+fn finish_fields(pending: &str) -> std::result::Result<(), ParseError> {
+    if pending.is_empty() {
+        return Ok(());
+    }
+    Err(ParseError::StructuralError {
+        context: pending.trim().to_string(),
+        issue: "annotation is not followed by a field reference".to_string(),
+    })
+}
+
+/// Check the arguments of `@deprecated("note")` or `@deprecated("note", Replacement)`
+#[allow(dead_code)]
+fn deprecation_issue(annotation: &Annotation) -> Option<String> {
+    match annotation.args.as_slice() {
+        [_] => None,
+        [_, replacement]
+            if replacement.starts_with(|c: char| c.is_alphabetic())
+                && replacement.chars().all(|c| c.is_alphanumeric() || c == '_') =>
+        {
+            None
+        }
+        [_, replacement] => Some(format!(
+            "@deprecated replacement '{replacement}' is not a term name"
+        )),
+        _ => Some("@deprecated takes a note and an optional replacement term".to_string()),
+    }
+}
+
+/// Parse `A -> B -> C; A -> D` chains, separated by `;` or new lines, into the states in
+/// order of first appearance and the transitions between them
+#[allow(dead_code)]
+pub fn parse_state_chains(
+    name: &str,
+    body: &str,
+) -> std::result::Result<(Vec<String>, Vec<Transition>), ParseError> {
+    let mut chains = Vec::new();
+    for chain in body.split([';', '\n']) {
+        let chain = chain.trim();
+        if chain.is_empty() || chain.starts_with("//") {
+            continue;
+        }
+        let chain: Vec<String> = chain
+            .split("->")
+            .map(|state| state.trim().to_string())
+            .collect();
+        chains.push(chain);
+    }
+    build_state_machine(name, chains)
+}
+
+/// Collect states and deduplicated transitions from parsed `A -> B -> C` chains
+#[allow(dead_code)]
+pub fn build_state_machine(
+    name: &str,
+    chains: Vec<Vec<String>>,
+) -> std::result::Result<(Vec<String>, Vec<Transition>), ParseError> {
+    let mut states: Vec<String> = Vec::new();
+    let mut transitions: Vec<Transition> = Vec::new();
+
+    for chain in chains {
+        for state in &chain {
+            let is_identifier = state.starts_with(|c: char| c.is_alphabetic())
+                && state.chars().all(|c| c.is_alphanumeric() || c == '_');
+            if !is_identifier {
+                return Err(ParseError::StructuralError {
+                    context: name.to_string(),
+                    issue: format!("'{state}' is not a valid state name"),
+                });
+            }
+            if !states.contains(state) {
+                states.push(state.clone());
+            }
+        }
+        for pair in chain.windows(2) {
+            let transition = (pair[0].clone(), pair[1].clone());
+            if !transitions.contains(&transition) {
+                transitions.push(transition);
+            }
+        }
+    }
+
+    if states.is_empty() {
+        return Err(ParseError::StructuralError {
+            context: name.to_string(),
+            issue: "a state machine needs at least one state".to_string(),
+        });
+    }
+    Ok((states, transitions))
+}
+
+/// `SocialMedia` becomes `social_media`, for generated names in Rust and in exported schemas
+#[allow(dead_code)]
+pub fn snake_case(name: &str) -> String {
+    let mut result = String::new();
+    for (index, c) in name.char_indices() {
+        if c.is_uppercase() && index > 0 {
+            result.push('_');
+        }
+        result.extend(c.to_lowercase());
+    }
+    result
 }